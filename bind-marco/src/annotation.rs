@@ -14,23 +14,45 @@ use crate::{
 pub struct Annotation {
     _at: Token![@],
     class_name: ClassName,
+    value: Option<AnnotationValue>,
+}
+
+struct AnnotationValue {
     _paren: Paren,
     value: LitStr,
 }
 
 impl Parse for Annotation {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let value_content;
-
         Ok(Annotation {
             _at: input.parse()?,
             class_name: input.parse()?,
+            value: if input.peek(Paren) { Some(input.parse()?) } else { None },
+        })
+    }
+}
+
+impl Parse for AnnotationValue {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let value_content;
+
+        Ok(AnnotationValue {
             _paren: parenthesized!(value_content in input),
             value: value_content.parse()?,
         })
     }
 }
 
+impl Annotation {
+    fn value_of(&self, name: &str) -> Option<&LitStr> {
+        if self.class_name.to_class_name() == name {
+            self.value.as_ref().map(|v| &v.value)
+        } else {
+            None
+        }
+    }
+}
+
 impl Repeatable for Annotation {
     fn should_continue(input: ParseStream) -> bool {
         input.peek(Token![@])
@@ -40,26 +62,35 @@ impl Repeatable for Annotation {
 pub trait AnnotationsExt {
     fn alias(&self) -> Option<Ident>;
     fn class_name(&self) -> Option<String>;
+    fn is_lazy(&self) -> bool;
+    fn panic_exception(&self) -> Option<String>;
+    fn export(&self) -> Option<String>;
 }
 
 impl AnnotationsExt for Repeat<Annotation> {
     fn alias(&self) -> Option<Ident> {
-        self.values().iter().find_map(|a| {
-            if a.class_name.to_class_name() == "Alias" {
-                Some(Ident::new(&a.value.value(), a.value.span()))
-            } else {
-                None
-            }
-        })
+        self.values()
+            .iter()
+            .find_map(|a| a.value_of("Alias"))
+            .map(|v| Ident::new(&v.value(), v.span()))
     }
 
     fn class_name(&self) -> Option<String> {
-        self.values().iter().find_map(|a| {
-            if a.class_name.to_class_name() == "ClassName" {
-                Some(a.value.value())
-            } else {
-                None
-            }
-        })
+        self.values().iter().find_map(|a| a.value_of("ClassName")).map(|v| v.value())
+    }
+
+    fn is_lazy(&self) -> bool {
+        self.values().iter().any(|a| a.class_name.to_class_name() == "Lazy")
+    }
+
+    fn panic_exception(&self) -> Option<String> {
+        self.values()
+            .iter()
+            .find_map(|a| a.value_of("PanicException"))
+            .map(|v| v.value())
+    }
+
+    fn export(&self) -> Option<String> {
+        self.values().iter().find_map(|a| a.value_of("Export")).map(|v| v.value())
     }
 }
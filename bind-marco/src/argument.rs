@@ -17,9 +17,9 @@ impl Parse for Argument {
     }
 }
 
-impl Into<(Ident, Type)> for &Argument {
-    fn into(self) -> (Ident, Type) {
-        (self.name.clone(), self.type_name.to_type())
+impl From<&Argument> for (Ident, Type) {
+    fn from(argument: &Argument) -> Self {
+        (argument.name.clone(), argument.type_name.to_type())
     }
 }
 
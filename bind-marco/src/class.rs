@@ -1,5 +1,5 @@
 use proc_macro2::{Ident, TokenStream};
-use quote::{quote, ToTokens};
+use quote::{format_ident, quote};
 use syn::{
     braced,
     parse::{Parse, ParseStream},
@@ -11,10 +11,13 @@ use syn::{
 use crate::{
     annotation::{Annotation, AnnotationsExt},
     member::Member,
-    member_impl::ImplForMember,
+    member_const::ConstForMember,
+    member_impl::{ImplForMember, Target},
     member_impl_bind::ImplBindForMember,
+    member_native::NativeForMember,
     member_struct::StructForMember,
     repeat::{Repeat, Repeatable},
+    types::TypeRegistry,
 };
 
 mod kw {
@@ -23,6 +26,13 @@ mod kw {
     custom_keyword!(class);
 }
 
+pub struct ClassContext<'a> {
+    pub handle: Ident,
+    pub lazy: bool,
+    pub panic_exception: String,
+    pub types: &'a TypeRegistry,
+}
+
 pub struct Class {
     annotations: Repeat<Annotation>,
     _class: kw::class,
@@ -42,6 +52,15 @@ impl Parse for Class {
             members: Punctuated::parse_terminated(&body_content)?,
         };
 
+        if let Some(export) = class.annotations.export() {
+            if class.annotations.class_name().is_none() {
+                return Err(syn::Error::new(class.name.span(), "exported classes require a class name."));
+            }
+
+            syn::parse_str::<syn::Path>(&export)
+                .map_err(|_| syn::Error::new(class.name.span(), "invalid export implementation path."))?;
+        }
+
         for member in &class.members {
             if let Member::Constructor { name, .. } = member {
                 if name != &class.name {
@@ -60,37 +79,201 @@ impl Repeatable for Class {
     }
 }
 
-impl ToTokens for Class {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl Class {
+    pub fn name(&self) -> &Ident {
+        &self.name
+    }
+
+    fn handle_name(&self) -> Ident {
+        format_ident!("{}Ref", self.name)
+    }
+
+    pub fn register_type(&self, types: &mut TypeRegistry) {
+        if let Some(class_name) = self.annotations.class_name() {
+            types.register(&class_name, self.handle_name());
+        }
+    }
+
+    pub fn render(&self, types: &TypeRegistry) -> TokenStream {
+        let mut tokens = TokenStream::new();
         let name = &self.name;
+        let handle = self.handle_name();
         let class_name = self.annotations.class_name();
-        let struct_fields = self.members.iter().map(|m| StructForMember::new(m));
-        let struct_impls = self.members.iter().map(|m| ImplForMember::new(m));
-        let struct_impl_bind = self.members.iter().map(|m| ImplBindForMember::new(m));
+        let lazy = self.annotations.is_lazy();
+        let class_ctx = ClassContext {
+            handle: handle.clone(),
+            lazy,
+            panic_exception: self
+                .annotations
+                .panic_exception()
+                .unwrap_or_else(|| "java.lang.RuntimeException".to_owned())
+                .replace('.', "/"),
+            types,
+        };
+        let bound_members = self.members.iter().filter(|m| !m.is_constant()).collect::<Vec<_>>();
+        let struct_fields = bound_members.iter().map(|m| StructForMember::new(m, lazy));
+        let struct_impls = self.members.iter().map(|m| ImplForMember::new(m, &class_ctx));
+        let struct_impl_bind = bound_members.iter().map(|m| ImplBindForMember::new(m, lazy));
+        let (class_field, class_bind) = if lazy {
+            (
+                quote! { class: ::bind_java::WeakRef, },
+                quote! { class: ::bind_java::WeakRef::new(ctx, class)?, },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+        // lazy bindings only hold the class weakly so that cached bindings do not pin their loader.
+        let unbind = if lazy {
+            quote! {
+                unsafe fn unbind(self, ctx: ::bind_java::Context) {
+                    self.class.delete(ctx)
+                }
+            }
+        } else {
+            quote! {}
+        };
+        let natives = self
+            .members
+            .iter()
+            .filter(|m| m.is_native())
+            .map(|m| NativeForMember::new(m, &class_ctx))
+            .collect::<Vec<_>>();
+
+        let mut reserved = Vec::new();
+        if class_name.is_some() {
+            reserved.extend(["bound", "bound_in", "loader_cache"]);
+        }
+        if !natives.is_empty() {
+            reserved.push("register_natives");
+        }
+        for member in &self.members {
+            let names = ImplForMember::new(member, &class_ctx).func_names();
+            if let Some(name) = names.iter().find(|n| reserved.contains(&n.to_string().as_str())) {
+                return syn::Error::new(
+                    name.span(),
+                    format!("`{}` collides with a generated function, rename it with @Alias.", name),
+                )
+                .to_compile_error();
+            }
+        }
+
+        let constant_checks = self
+            .members
+            .iter()
+            .filter(|m| m.is_constant())
+            .map(|m| ConstForMember::new(m).check());
 
         tokens.extend(quote! {
             struct #name {
+                #class_field
                 #(#struct_fields),*
             }
 
-            unsafe impl Sync for #name {}
-            unsafe impl Send for #name {}
-
             impl #name {
                 #(#struct_impls)*
             }
 
             impl ::bind_java::ClassBinding for #name {
                 unsafe fn bind(ctx: ::bind_java::Context, class: ::bind_java::Class) -> ::bind_java::Result<Self> {
+                    #(#constant_checks)*
+
                     Ok(#name {
+                        #class_bind
                         #(#struct_impl_bind),*
                     })
                 }
+
+                #unbind
+            }
+
+            #[repr(transparent)]
+            #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+            struct #handle(::bind_java::Object);
+
+            unsafe impl ::bind_java::Reference for #handle {
+                fn as_object(self) -> ::bind_java::Object {
+                    self.0
+                }
+
+                unsafe fn from_object(object: ::bind_java::Object) -> Self {
+                    #handle(object)
+                }
+            }
+
+            impl ::bind_java::FromJava<#handle> for ::bind_java::Object {
+                unsafe fn from_java(value: #handle, _: ::bind_java::Context) -> ::bind_java::Result<Self> {
+                    Ok(value.0)
+                }
+            }
+
+            impl ::std::convert::From<#handle> for ::bind_java::Object {
+                fn from(value: #handle) -> Self {
+                    value.0
+                }
+            }
+
+            impl #handle {
+                pub unsafe fn downcast_with(ctx: ::bind_java::Context, object: ::bind_java::Object, class: ::bind_java::Class) -> ::std::option::Option<Self> {
+                    if ::bind_java::is_instance_of(ctx, object, class) {
+                        Some(#handle(object))
+                    } else {
+                        None
+                    }
+                }
             }
         });
 
+        if !natives.is_empty() {
+            let natives_trait = format_ident!("{}Natives", name);
+            let trait_methods = natives.iter().map(|n| n.trait_method());
+            let shims = natives.iter().map(|n| n.shim(&natives_trait));
+            let entries = natives.iter().map(|n| n.entry());
+            let exports = match (self.annotations.export(), &class_name) {
+                (Some(export), Some(class_name)) => {
+                    let implementor = syn::parse_str::<syn::Path>(&export).unwrap();
+
+                    natives
+                        .iter()
+                        .map(|n| {
+                            let overloaded = natives.iter().filter(|o| o.java_name() == n.java_name()).count() > 1;
+
+                            n.export(class_name, &natives_trait, &implementor, overloaded)
+                        })
+                        .collect::<Vec<_>>()
+                }
+                _ => Vec::new(),
+            };
+
+            tokens.extend(quote! {
+                trait #natives_trait {
+                    #(#trait_methods)*
+                }
+
+                impl #name {
+                    pub fn register_natives<N: #natives_trait>(ctx: ::bind_java::Context, class: ::bind_java::Class) -> ::bind_java::Result<()> {
+                        #(#shims)*
+
+                        ::bind_java::register_native_methods(ctx, class, &[#(#entries),*])
+                    }
+                }
+
+                #(#exports)*
+            });
+        }
+
         if let Some(class_name) = class_name {
             let internal_class_name = class_name.replace('.', "/");
+            let signature = format!("L{};", internal_class_name);
+            let instance = format_ident!("{}Instance", name);
+            let statics = format_ident!("{}Statics", name);
+            let instance_funcs = self
+                .members
+                .iter()
+                .map(|m| ImplForMember::new(m, &class_ctx).view(Target::This));
+            let statics_funcs = self
+                .members
+                .iter()
+                .map(|m| ImplForMember::new(m, &class_ctx).view(Target::Class));
 
             tokens.extend(quote! {
                 impl ::bind_java::WithClass for #name {
@@ -104,7 +287,94 @@ impl ToTokens for Class {
                         }
                     }
                 }
+
+                unsafe impl ::bind_java::ArrayElement for #handle {
+                    type Raw = #handle;
+
+                    fn signature() -> ::std::string::String {
+                        #signature.to_owned()
+                    }
+
+                    // classes bound through a loader are only reachable from it, find_class knows the global loaders.
+                    fn element_class(ctx: ::bind_java::Context) -> ::bind_java::Result<::bind_java::Class> {
+                        match #name::loader_cache().latest_class(ctx) {
+                            Some(class) => Ok(class),
+                            None => ::bind_java::find_class(ctx, #internal_class_name),
+                        }
+                    }
+                }
+
+                impl #name {
+                    pub fn bound(ctx: ::bind_java::Context) -> ::bind_java::Result<&'static ::bind_java::Bound<Self>> {
+                        static BOUND: ::bind_java::BindingCell<#name> = ::bind_java::BindingCell::new();
+
+                        BOUND.get_or_bind(ctx)
+                    }
+
+                    pub fn bound_in(ctx: ::bind_java::Context, loader: &dyn ::bind_java::ClassLoader) -> ::bind_java::Result<::bind_java::LoaderBound<Self>> {
+                        Self::loader_cache().get(ctx, loader)
+                    }
+
+                    fn loader_cache() -> &'static ::bind_java::LoaderCache<Self> {
+                        static CACHE: ::bind_java::LoaderCache<#name> = ::bind_java::LoaderCache::new();
+
+                        &CACHE
+                    }
+                }
+
+                impl #handle {
+                    pub fn on(self, ctx: ::bind_java::Context) -> ::bind_java::Result<#instance<'static>> {
+                        Ok(self.on_in(ctx, #name::bound(ctx)?))
+                    }
+
+                    pub fn on_in<'b>(self, ctx: ::bind_java::Context, bound: &'b impl ::bind_java::BindingRef<#name>) -> #instance<'b> {
+                        #instance { ctx, binding: bound.binding(), this: self }
+                    }
+
+                    pub fn statics(ctx: ::bind_java::Context) -> ::bind_java::Result<#statics<'static>> {
+                        Ok(Self::statics_in(ctx, #name::bound(ctx)?))
+                    }
+
+                    pub fn statics_in<'b>(ctx: ::bind_java::Context, bound: &'b impl ::bind_java::BindingRef<#name>) -> #statics<'b> {
+                        #statics { ctx, class: bound.class(), binding: bound.binding() }
+                    }
+                }
+
+                // views keep the binding they were created from, so classes from other loaders stay with it.
+                #[derive(Copy, Clone)]
+                struct #instance<'b> {
+                    ctx: ::bind_java::Context,
+                    binding: &'b #name,
+                    this: #handle,
+                }
+
+                impl #instance<'_> {
+                    pub fn this(&self) -> #handle {
+                        self.this
+                    }
+
+                    #(#instance_funcs)*
+                }
+
+                #[derive(Copy, Clone)]
+                struct #statics<'b> {
+                    ctx: ::bind_java::Context,
+                    class: ::bind_java::Class,
+                    binding: &'b #name,
+                }
+
+                impl #statics<'_> {
+                    #(#statics_funcs)*
+                }
+
+                impl #handle {
+                    pub unsafe fn downcast(ctx: ::bind_java::Context, object: ::bind_java::Object) -> ::bind_java::Result<::std::option::Option<Self>> {
+                        Ok(Self::downcast_with(ctx, object, #name::bound(ctx)?.class()))
+                    }
+                }
             });
         }
+
+        tokens
     }
 }
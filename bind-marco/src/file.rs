@@ -2,22 +2,41 @@ use proc_macro2::TokenStream;
 use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
 
-use crate::{class::Class, repeat::Repeat};
+use crate::{class::Class, repeat::Repeat, type_map::TypeMap, types::TypeRegistry};
 
 pub struct File {
+    mappings: Repeat<TypeMap>,
     classes: Repeat<Class>,
 }
 
 impl Parse for File {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        Ok(File { classes: input.parse()? })
+        Ok(File {
+            mappings: input.parse()?,
+            classes: input.parse()?,
+        })
     }
 }
 
 impl ToTokens for File {
     fn to_tokens(&self, tokens: &mut TokenStream) {
+        let mut types = TypeRegistry::default();
+        for mapping in self.mappings.values() {
+            types.map(&mapping.java_type(), mapping.rust_type().clone());
+        }
+
+        for class in self.classes.values() {
+            class.register_type(&mut types);
+        }
+
+        if let Some(class) = self.classes.values().first() {
+            types.scope_elements(class.name());
+        }
+
         for class in self.classes.values() {
-            class.to_tokens(tokens)
+            tokens.extend(class.render(&types));
         }
+
+        tokens.extend(types.render_mapped_elements());
     }
 }
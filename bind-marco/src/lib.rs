@@ -1,23 +1,42 @@
 use quote::ToTokens;
+use syn::{meta, parse_macro_input, ItemFn};
 
-use crate::file::File;
+use crate::{file::File, native_fn::NativeFnArgs};
 
 mod annotation;
 mod argument;
 mod class;
 mod file;
+mod mangle;
 mod member;
+mod member_const;
 mod member_impl;
 mod member_impl_bind;
+mod member_native;
 mod member_struct;
 mod modifier;
+mod native_fn;
 mod repeat;
 mod signature;
+mod type_map;
 mod types;
 
 #[proc_macro]
 pub fn bind_java(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
-    let file = syn::parse_macro_input!(input as File);
+    let file = parse_macro_input!(input as File);
 
     file.into_token_stream().into()
 }
+
+#[proc_macro_attribute]
+pub fn native(args: proc_macro::TokenStream, input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let mut native_args = NativeFnArgs::default();
+    let parser = meta::parser(|meta| native_args.parse(meta));
+    parse_macro_input!(args with parser);
+
+    let func = parse_macro_input!(input as ItemFn);
+
+    native_fn::expand(native_args, func)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
@@ -0,0 +1,32 @@
+pub fn mangle(name: &str) -> String {
+    let mut result = String::new();
+
+    for c in name.chars() {
+        match c {
+            '/' | '.' => result.push('_'),
+            '_' => result.push_str("_1"),
+            ';' => result.push_str("_2"),
+            '[' => result.push_str("_3"),
+            c if c.is_ascii_alphanumeric() => result.push(c),
+            c => {
+                let mut buf = [0u16; 2];
+                for unit in c.encode_utf16(&mut buf) {
+                    result.push_str(&format!("_0{:04x}", unit));
+                }
+            }
+        }
+    }
+
+    result
+}
+
+pub fn export_name(class_name: &str, method_name: &str, arguments_signature: Option<&str>) -> String {
+    let mut result = format!("Java_{}_{}", mangle(class_name), mangle(method_name));
+
+    if let Some(signature) = arguments_signature {
+        result.push_str("__");
+        result.push_str(&mangle(signature));
+    }
+
+    result
+}
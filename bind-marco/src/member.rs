@@ -5,15 +5,15 @@ use syn::{
     parse::{Parse, ParseStream},
     punctuated::Punctuated,
     token::Paren,
-    Token,
+    Expr, ExprLit, ExprUnary, Lit, Token, UnOp,
 };
 
 use crate::{
     annotation::{Annotation, AnnotationsExt},
     argument::Argument,
-    modifier::Modifier,
+    modifier::{Modifier, ModifiersExt},
     repeat::Repeat,
-    types::TypeName,
+    types::{Type, TypeName},
 };
 
 pub enum Member {
@@ -36,15 +36,52 @@ pub enum Member {
         modifiers: Repeat<Modifier>,
         field_type: TypeName,
         name: Ident,
+        initializer: Option<Initializer>,
     },
 }
 
+pub struct Initializer {
+    _eq: Token![=],
+    value: Expr,
+}
+
+impl Parse for Initializer {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let initializer = Initializer {
+            _eq: input.parse()?,
+            value: input.parse()?,
+        };
+
+        let literal = match &initializer.value {
+            Expr::Lit(_) => true,
+            Expr::Unary(ExprUnary {
+                op: UnOp::Neg(_), expr, ..
+            }) => matches!(expr.as_ref(), Expr::Lit(_)),
+            _ => false,
+        };
+        if !literal {
+            return Err(syn::Error::new_spanned(
+                &initializer.value,
+                "constant initializer must be a literal.",
+            ));
+        }
+
+        Ok(initializer)
+    }
+}
+
+impl Initializer {
+    pub fn value(&self) -> &Expr {
+        &self.value
+    }
+}
+
 impl Parse for Member {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let annotations: Repeat<Annotation> = input.parse()?;
 
         if let Some((_, cursor)) = input.cursor().ident() {
-            if let Some(_) = cursor.group(Delimiter::Parenthesis) {
+            if cursor.group(Delimiter::Parenthesis).is_some() {
                 let arguments_content;
                 return Ok(Member::Constructor {
                     annotations,
@@ -58,7 +95,7 @@ impl Parse for Member {
         let modifiers: Repeat<Modifier> = input.parse()?;
         let type_name: TypeName = input.parse()?;
         let name: Ident = input.parse()?;
-        if let Some(_) = input.cursor().group(Delimiter::Parenthesis) {
+        if input.cursor().group(Delimiter::Parenthesis).is_some() {
             let arguments_content;
 
             Ok(Member::Method {
@@ -70,17 +107,61 @@ impl Parse for Member {
                 arguments: Punctuated::parse_terminated(&arguments_content)?,
             })
         } else {
+            let initializer = if input.peek(Token![=]) {
+                let initializer: Initializer = input.parse()?;
+
+                if !modifiers.is_static() || !modifiers.is_final() {
+                    return Err(syn::Error::new(name.span(), "only static final fields can be constant."));
+                }
+                if !type_name.to_type().is_constant_type() {
+                    return Err(syn::Error::new(
+                        name.span(),
+                        "only primitive or java.lang.String fields can be constant.",
+                    ));
+                }
+                if matches!(type_name.to_type(), Type::Char) {
+                    let Expr::Lit(ExprLit { lit: Lit::Char(ch), .. }) = initializer.value() else {
+                        return Err(syn::Error::new(name.span(), "char constants must be a character literal."));
+                    };
+                    if u16::try_from(ch.value() as u32).is_err() {
+                        return Err(syn::Error::new(
+                            ch.span(),
+                            "char constants must be in the basic multilingual plane.",
+                        ));
+                    }
+                }
+
+                Some(initializer)
+            } else {
+                None
+            };
+
             Ok(Member::Field {
                 annotations,
                 modifiers,
                 field_type: type_name,
                 name,
+                initializer,
             })
         }
     }
 }
 
 impl Member {
+    pub fn is_constant(&self) -> bool {
+        matches!(
+            self,
+            Member::Field {
+                initializer: Some(_),
+                ..
+            }
+        )
+    }
+
+    pub fn is_native(&self) -> bool {
+        matches!(self, Member::Method { modifiers, .. } if modifiers.is_native())
+    }
+
     pub fn resolve_rust_name(&self) -> Ident {
         fn resolve(annotations: &Repeat<Annotation>, name: &Ident) -> Ident {
             if let Some(alias) = annotations.alias() {
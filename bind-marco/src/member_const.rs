@@ -0,0 +1,77 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, TokenStream};
+use quote::{quote, ToTokens};
+
+use crate::{member::Member, types::Type};
+
+pub struct ConstForMember<'a> {
+    member: &'a Member,
+}
+
+impl<'a> ConstForMember<'a> {
+    pub fn new(member: &'a Member) -> Self {
+        ConstForMember { member }
+    }
+
+    pub fn const_name(&self) -> Ident {
+        let rs_name = self.member.resolve_rust_name();
+
+        Ident::new(&rs_name.to_string().to_case(Case::UpperSnake), rs_name.span())
+    }
+
+    pub fn check(&self) -> TokenStream {
+        let Member::Field { name, field_type, .. } = self.member else {
+            return quote! {};
+        };
+
+        let const_name = self.const_name();
+        let name = name.to_string();
+        let field_type = field_type.to_type();
+        let signature = field_type.to_signature();
+        let jni_type = field_type.render_raw_type();
+        let value_type = match field_type {
+            Type::String => quote! { ::std::string::String },
+            Type::Char => quote! { char },
+            _ => jni_type.clone(),
+        };
+
+        quote! {
+            #[cfg(debug_assertions)]
+            ::bind_java::check_constant_field::<#jni_type, #value_type, _>(ctx, class, #name, #signature, Self::#const_name)?;
+        }
+    }
+}
+
+impl<'a> ToTokens for ConstForMember<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Member::Field {
+            field_type,
+            initializer: Some(initializer),
+            ..
+        } = self.member
+        else {
+            return;
+        };
+
+        let const_name = self.const_name();
+        let value = initializer.value();
+
+        let ts = match field_type.to_type() {
+            Type::String => quote! {
+                pub const #const_name: &'static str = #value;
+            },
+            Type::Char => quote! {
+                pub const #const_name: char = #value;
+            },
+            typ => {
+                let jni_type = typ.render_raw_type();
+
+                quote! {
+                    pub const #const_name: #jni_type = #value;
+                }
+            }
+        };
+
+        tokens.extend(ts);
+    }
+}
@@ -3,7 +3,10 @@ use proc_macro2::{Ident, Span, TokenStream};
 use quote::{format_ident, quote, ToTokens};
 
 use crate::{
+    class::ClassContext,
     member::Member,
+    member_const::ConstForMember,
+    member_impl_bind::ImplBindForMember,
     member_struct::StructForMember,
     modifier::{Modifier, ModifiersExt},
     repeat::Repeat,
@@ -11,8 +14,8 @@ use crate::{
     types::Type,
 };
 
-#[derive(Copy, Clone)]
-enum Target {
+#[derive(Copy, Clone, Eq, PartialEq)]
+pub enum Target {
     This,
     Class,
 }
@@ -32,57 +35,36 @@ enum ArgumentsTransform {
     JValueArray,
 }
 
-fn build_invoke_func<'a>(
-    name: &Ident,
-    return_type: &Type,
+struct InvokeFunc {
+    name: Ident,
+    return_type: Option<TokenStream>,
+    default_return_type: Option<TokenStream>,
     target: Target,
-    arguments: &[(Ident, Type)],
-    func_name: &Ident,
+    arguments: Vec<(Ident, Type)>,
+    func_name: &'static str,
     arguments_transform: ArgumentsTransform,
-    invoke_id: &Ident,
-) -> TokenStream {
-    let (generic_list, return_type) = match return_type {
-        Type::Void => (quote! {}, quote! { () }),
-        typ => {
-            let return_type = typ.render_jni_type();
-
-            (quote! { <R: ::bind_java::FromJava<#return_type>> }, quote! { R })
-        }
-    };
-    let (target_name, target_type) = match target {
-        Target::This => (Ident::new("this", Span::call_site()), quote! { ::bind_java::Object }),
-        Target::Class => (Ident::new("class", Span::call_site()), quote! { ::bind_java::Class }),
-    };
-    let args_names = arguments
-        .iter()
-        .map(|a| Ident::new(&a.0.to_string().to_case(Case::Snake), a.0.span()))
-        .collect::<Vec<_>>();
-    let args_types = arguments.iter().map(|a| a.1.render_jni_type()).collect::<Vec<_>>();
-
-    let body = match arguments_transform {
-        ArgumentsTransform::JTypedFlatten => {
-            quote! {
-                ::bind_java::#func_name(ctx, #target_name, self.#invoke_id, #((#args_names).into_java(ctx)?),*)
-            }
-        }
-        ArgumentsTransform::JValueArray => {
-            quote! {
-                use ::bind_java::IntoValue;
+}
 
-                ::bind_java::#func_name(ctx, #target_name, self.#invoke_id, &[#((#args_names).into_java(ctx)?.into_value()),*])
-            }
+impl InvokeFunc {
+    fn variants(&self) -> Vec<(Ident, TokenStream, TokenStream)> {
+        match (&self.return_type, &self.default_return_type) {
+            (Some(return_type), Some(default_return_type)) => vec![
+                (self.name.clone(), quote! {}, default_return_type.clone()),
+                (
+                    format_ident!("{}_as", self.name),
+                    quote! { <R: ::bind_java::FromJava<#return_type>> },
+                    quote! { R },
+                ),
+            ],
+            _ => vec![(self.name.clone(), quote! {}, quote! { () })],
         }
-    };
+    }
 
-    quote! {
-        pub unsafe fn #name #generic_list (
-            &self,
-            ctx: ::bind_java::Context,
-            #target_name: #target_type,
-            #(#args_names: impl ::bind_java::IntoJava<#args_types>),*
-        ) -> ::bind_java::Result<#return_type> {
-            #body
-        }
+    fn args_names(&self) -> Vec<Ident> {
+        self.arguments
+            .iter()
+            .map(|a| Ident::new(&a.0.to_string().to_case(Case::Snake), a.0.span()))
+            .collect()
     }
 }
 
@@ -95,8 +77,8 @@ fn build_register_func(
 ) -> TokenStream {
     let signature = signature::method_signature(return_type, arguments.iter().map(|t| t.1.clone()));
 
-    let args_types = arguments.iter().map(|t| t.1.render_jni_type()).collect::<Vec<_>>();
-    let return_type = return_type.render_jni_type();
+    let args_types = arguments.iter().map(|t| t.1.render_raw_type()).collect::<Vec<_>>();
+    let return_type = return_type.render_raw_type();
     let target_type = match target {
         Target::This => quote! { ::bind_java::Object },
         Target::Class => quote! { ::bind_java::Class },
@@ -125,119 +107,260 @@ fn build_register_func(
 
 pub struct ImplForMember<'a> {
     member: &'a Member,
+    class_ctx: &'a ClassContext<'a>,
 }
 
 impl<'a> ImplForMember<'a> {
-    pub fn new(member: &'a Member) -> Self {
-        ImplForMember { member }
+    pub fn new(member: &'a Member, class_ctx: &'a ClassContext<'a>) -> Self {
+        ImplForMember { member, class_ctx }
     }
-}
 
-impl<'a> ToTokens for ImplForMember<'a> {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+    fn render_return_type(&self, typ: &Type) -> Option<TokenStream> {
+        match typ {
+            Type::Void => None,
+            typ => Some(self.class_ctx.types.render_type(typ)),
+        }
+    }
+
+    fn render_default_return_type(&self, typ: &Type) -> Option<TokenStream> {
+        match typ {
+            Type::Void => None,
+            typ => Some(self.class_ctx.types.render_rust_type(typ)),
+        }
+    }
+
+    fn build_invoke_func(&self, func: &InvokeFunc) -> TokenStream {
+        let invoke_id = self.invoke_id();
+        let func_name = Ident::new(func.func_name, Span::call_site());
+        let func_name = match &func.return_type {
+            Some(return_type) => quote! { #func_name::<#return_type, _> },
+            None => quote! { #func_name },
+        };
+        let (target_name, target_type) = match func.target {
+            Target::This => {
+                let handle = &self.class_ctx.handle;
+
+                (Ident::new("this", Span::call_site()), quote! { #handle })
+            }
+            Target::Class => (Ident::new("class", Span::call_site()), quote! { ::bind_java::Class }),
+        };
+        let args_names = func.args_names();
+        let args_types = func
+            .arguments
+            .iter()
+            .map(|a| self.class_ctx.types.render_argument_type(&a.1))
+            .collect::<Vec<_>>();
+        let args_jni_types = func
+            .arguments
+            .iter()
+            .map(|a| self.class_ctx.types.render_type(&a.1))
+            .collect::<Vec<_>>();
+
+        let body = match func.arguments_transform {
+            ArgumentsTransform::JTypedFlatten => {
+                quote! {
+                    ::bind_java::#func_name(ctx, ::bind_java::Reference::as_object(#target_name), #invoke_id, #(::bind_java::IntoJava::<#args_jni_types>::into_java(#args_names, ctx)?),*)
+                }
+            }
+            ArgumentsTransform::JValueArray => {
+                quote! {
+                    use ::bind_java::IntoValue;
+
+                    ::bind_java::#func_name(ctx, ::bind_java::Reference::as_object(#target_name), #invoke_id, &[#(::bind_java::IntoJava::<#args_jni_types>::into_java(#args_names, ctx)?.into_value()),*])
+                }
+            }
+        };
+
+        let funcs = func.variants().into_iter().map(|(name, generic_list, return_type)| {
+            quote! {
+                pub unsafe fn #name #generic_list (
+                    &self,
+                    ctx: ::bind_java::Context,
+                    #target_name: #target_type,
+                    #(#args_names: #args_types),*
+                ) -> ::bind_java::Result<#return_type> {
+                    #body
+                }
+            }
+        });
+
+        quote! { #(#funcs)* }
+    }
+
+    fn build_view_func(&self, func: &InvokeFunc) -> TokenStream {
+        let args_names = func.args_names();
+        let args_types = func
+            .arguments
+            .iter()
+            .map(|a| self.class_ctx.types.render_argument_type(&a.1))
+            .collect::<Vec<_>>();
+        let target = match func.target {
+            Target::This => quote! { self.this },
+            Target::Class => quote! { self.class },
+        };
+
+        let funcs = func.variants().into_iter().map(|(name, generic_list, return_type)| {
+            quote! {
+                pub unsafe fn #name #generic_list (
+                    &self,
+                    #(#args_names: #args_types),*
+                ) -> ::bind_java::Result<#return_type> {
+                    self.binding.#name(self.ctx, #target, #(#args_names),*)
+                }
+            }
+        });
+
+        quote! { #(#funcs)* }
+    }
+
+    pub fn view(&self, target: Target) -> TokenStream {
+        let funcs = self
+            .invoke_funcs()
+            .into_iter()
+            .filter(|f| f.target == target)
+            .map(|f| self.build_view_func(&f));
+
+        quote! { #(#funcs)* }
+    }
+
+    pub fn func_names(&self) -> Vec<Ident> {
+        let mut names = self
+            .invoke_funcs()
+            .iter()
+            .flat_map(|f| f.variants().into_iter().map(|v| v.0))
+            .collect::<Vec<_>>();
+
+        if self.member.is_native() {
+            names.push(format_ident!("register_{}", self.member.resolve_rust_name()));
+        }
+
+        names
+    }
+
+    fn invoke_funcs(&self) -> Vec<InvokeFunc> {
         let rs_name = self.member.resolve_rust_name();
-        let field_name = StructForMember::new(&self.member).field_name();
 
         match &self.member {
-            Member::Constructor {
-                annotations: _annotations,
-                name: _name,
-                _paren,
-                arguments,
-            } => {
-                tokens.extend(build_invoke_func(
-                    &rs_name,
-                    &Type::Object("java/lang/Object".to_owned()),
-                    Target::Class,
-                    &arguments.iter().map(|a| a.into()).collect::<Vec<_>>(),
-                    &Ident::new("new_object", Span::call_site()),
-                    ArgumentsTransform::JValueArray,
-                    &field_name,
-                ));
+            Member::Constructor { arguments, .. } => {
+                let handle = &self.class_ctx.handle;
+
+                vec![InvokeFunc {
+                    name: rs_name,
+                    return_type: Some(quote! { #handle }),
+                    default_return_type: Some(quote! { #handle }),
+                    target: Target::Class,
+                    arguments: arguments.iter().map(|a| a.into()).collect(),
+                    func_name: "new_object",
+                    arguments_transform: ArgumentsTransform::JValueArray,
+                }]
             }
             Member::Method {
-                annotations: _annotations,
                 modifiers,
                 return_type,
-                name,
-                _paren,
                 arguments,
-            } => {
-                let return_type = return_type.to_type();
-                let target = Target::from_modifiers(modifiers);
-                let arguments = arguments.iter().map(|a| a.into()).collect::<Vec<_>>();
-
-                tokens.extend(build_invoke_func(
-                    &rs_name,
-                    &return_type,
-                    target,
-                    &arguments,
-                    &Ident::new(
-                        if modifiers.is_static() {
-                            "call_static_method"
-                        } else {
-                            "call_method"
-                        },
-                        Span::call_site(),
-                    ),
-                    ArgumentsTransform::JValueArray,
-                    &field_name,
-                ));
-
-                if modifiers.is_native() {
-                    tokens.extend(build_register_func(
-                        &format_ident!("register_{}", rs_name),
-                        &return_type,
-                        target,
-                        &name.to_string(),
-                        &arguments,
-                    ))
-                }
-            }
+                ..
+            } => vec![InvokeFunc {
+                name: rs_name,
+                return_type: self.render_return_type(&return_type.to_type()),
+                default_return_type: self.render_default_return_type(&return_type.to_type()),
+                target: Target::from_modifiers(modifiers),
+                arguments: arguments.iter().map(|a| a.into()).collect(),
+                func_name: if modifiers.is_static() {
+                    "call_static_method"
+                } else {
+                    "call_method"
+                },
+                arguments_transform: ArgumentsTransform::JValueArray,
+            }],
+            Member::Field {
+                initializer: Some(_), ..
+            } => vec![],
             Member::Field {
-                annotations: _annotations,
                 modifiers,
                 field_type,
-                name: _name,
+                initializer: None,
+                ..
             } => {
                 let field_type = field_type.to_type();
+                let mut funcs = vec![InvokeFunc {
+                    name: format_ident!("get_{}", rs_name),
+                    return_type: self.render_return_type(&field_type),
+                    default_return_type: self.render_default_return_type(&field_type),
+                    target: Target::from_modifiers(modifiers),
+                    arguments: vec![],
+                    func_name: if modifiers.is_static() {
+                        "get_static_field"
+                    } else {
+                        "get_field"
+                    },
+                    arguments_transform: ArgumentsTransform::JTypedFlatten,
+                }];
 
-                tokens.extend(build_invoke_func(
-                    &format_ident!("get_{}", rs_name),
-                    &field_type,
-                    Target::from_modifiers(modifiers),
-                    &[],
-                    &Ident::new(
-                        if modifiers.is_static() {
-                            "get_static_field"
+                if !modifiers.is_final() {
+                    funcs.push(InvokeFunc {
+                        name: format_ident!("set_{}", rs_name),
+                        return_type: None,
+                        default_return_type: None,
+                        target: Target::from_modifiers(modifiers),
+                        arguments: vec![(Ident::new("value", rs_name.span()), field_type)],
+                        func_name: if modifiers.is_static() {
+                            "set_static_field"
                         } else {
-                            "get_field"
+                            "set_field"
                         },
-                        Span::call_site(),
-                    ),
-                    ArgumentsTransform::JTypedFlatten,
-                    &field_name,
-                ));
-
-                if !modifiers.is_final() {
-                    tokens.extend(build_invoke_func(
-                        &format_ident!("set_{}", rs_name),
-                        &Type::Void,
-                        Target::from_modifiers(modifiers),
-                        &[(Ident::new("value", rs_name.span()), field_type)],
-                        &Ident::new(
-                            if modifiers.is_static() {
-                                "set_static_field"
-                            } else {
-                                "set_field"
-                            },
-                            Span::call_site(),
-                        ),
-                        ArgumentsTransform::JTypedFlatten,
-                        &field_name,
-                    ));
+                        arguments_transform: ArgumentsTransform::JTypedFlatten,
+                    });
                 }
+
+                funcs
+            }
+        }
+    }
+
+    fn invoke_id(&self) -> TokenStream {
+        let lazy = self.class_ctx.lazy;
+        let field_name = StructForMember::new(self.member, lazy).field_name();
+
+        if lazy {
+            let find = ImplBindForMember::new(self.member, lazy).find_expr();
+
+            quote! {
+                self.#field_name.get_or_find(|| self.class.with_upgraded(ctx, |class| #find))?
+            }
+        } else {
+            quote! { self.#field_name }
+        }
+    }
+}
+
+impl<'a> ToTokens for ImplForMember<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for func in self.invoke_funcs() {
+            tokens.extend(self.build_invoke_func(&func));
+        }
+
+        match &self.member {
+            Member::Method {
+                modifiers,
+                return_type,
+                name,
+                arguments,
+                ..
+            } if modifiers.is_native() => {
+                tokens.extend(build_register_func(
+                    &format_ident!("register_{}", self.member.resolve_rust_name()),
+                    &return_type.to_type(),
+                    Target::from_modifiers(modifiers),
+                    &name.to_string(),
+                    &arguments.iter().map(|a| a.into()).collect::<Vec<_>>(),
+                ));
+            }
+            Member::Field {
+                initializer: Some(_), ..
+            } => {
+                ConstForMember::new(self.member).to_tokens(tokens);
             }
+            _ => {}
         }
     }
 }
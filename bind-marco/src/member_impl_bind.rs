@@ -5,24 +5,21 @@ use crate::{member::Member, member_struct::StructForMember, modifier::ModifiersE
 
 pub struct ImplBindForMember<'a> {
     member: &'a Member,
+    lazy: bool,
 }
 
 impl<'a> ImplBindForMember<'a> {
-    pub(crate) fn new(m: &'a Member) -> Self {
-        ImplBindForMember { member: m }
+    pub(crate) fn new(m: &'a Member, lazy: bool) -> Self {
+        ImplBindForMember { member: m, lazy }
     }
-}
 
-impl<'a> ToTokens for ImplBindForMember<'a> {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
-        let field_name = StructForMember::new(self.member).field_name();
-
-        let ts = match self.member {
+    pub fn find_expr(&self) -> TokenStream {
+        match self.member {
             Member::Constructor { arguments, .. } => {
                 let signature = signature::method_signature(&Type::Void, arguments.iter().map(|a| a.type_name().to_type()));
 
                 quote! {
-                    #field_name: ::bind_java::find_method(ctx, class, "<init>", #signature)?
+                    ::bind_java::find_method(ctx, class, "<init>", #signature)
                 }
             }
             Member::Method {
@@ -38,11 +35,11 @@ impl<'a> ToTokens for ImplBindForMember<'a> {
 
                 if modifiers.is_static() {
                     quote! {
-                        #field_name: ::bind_java::find_static_method(ctx, class, #name, #signature)?
+                        ::bind_java::find_static_method(ctx, class, #name, #signature)
                     }
                 } else {
                     quote! {
-                        #field_name: ::bind_java::find_method(ctx, class, #name, #signature)?
+                        ::bind_java::find_method(ctx, class, #name, #signature)
                     }
                 }
             }
@@ -57,16 +54,32 @@ impl<'a> ToTokens for ImplBindForMember<'a> {
 
                 if modifiers.is_static() {
                     quote! {
-                        #field_name: ::bind_java::find_static_field(ctx, class, #name, #signature)?
+                        ::bind_java::find_static_field(ctx, class, #name, #signature)
                     }
                 } else {
                     quote! {
-                        #field_name: ::bind_java::find_field(ctx, class, #name, #signature)?
+                        ::bind_java::find_field(ctx, class, #name, #signature)
                     }
                 }
             }
-        };
+        }
+    }
+}
+
+impl<'a> ToTokens for ImplBindForMember<'a> {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let field_name = StructForMember::new(self.member, self.lazy).field_name();
+
+        if self.lazy {
+            tokens.extend(quote! {
+                #field_name: ::bind_java::LazyId::new()
+            });
+        } else {
+            let find = self.find_expr();
 
-        tokens.extend(ts);
+            tokens.extend(quote! {
+                #field_name: #find?
+            });
+        }
     }
 }
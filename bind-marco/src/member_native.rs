@@ -0,0 +1,165 @@
+use convert_case::{Case, Casing};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+
+use crate::{class::ClassContext, mangle, member::Member, member_impl::Target, signature, types::Type};
+
+pub struct NativeForMember<'a> {
+    member: &'a Member,
+    class_ctx: &'a ClassContext<'a>,
+}
+
+impl<'a> NativeForMember<'a> {
+    pub fn new(member: &'a Member, class_ctx: &'a ClassContext<'a>) -> Self {
+        NativeForMember { member, class_ctx }
+    }
+
+    fn parts(&self) -> (Type, Target, Vec<(Ident, Type)>) {
+        let Member::Method {
+            modifiers,
+            return_type,
+            arguments,
+            ..
+        } = self.member
+        else {
+            unreachable!("native members are always methods")
+        };
+
+        let arguments = arguments
+            .iter()
+            .map(|a| {
+                let (name, typ): (Ident, Type) = a.into();
+
+                (Ident::new(&name.to_string().to_case(Case::Snake), name.span()), typ)
+            })
+            .collect();
+
+        (return_type.to_type(), Target::from_modifiers(modifiers), arguments)
+    }
+
+    fn target(&self, target: Target) -> (Ident, TokenStream) {
+        match target {
+            Target::This => {
+                let handle = &self.class_ctx.handle;
+
+                (Ident::new("this", Span::call_site()), quote! { #handle })
+            }
+            Target::Class => (Ident::new("class", Span::call_site()), quote! { ::bind_java::Class }),
+        }
+    }
+
+    // java may pass null for any string, so the implementor has to handle the missing value.
+    fn render_argument_type(&self, typ: &Type) -> TokenStream {
+        let rust_type = self.class_ctx.types.render_rust_type(typ);
+
+        match typ {
+            Type::String => quote! { ::std::option::Option<#rust_type> },
+            _ => rust_type,
+        }
+    }
+
+    pub fn trait_method(&self) -> TokenStream {
+        let types = self.class_ctx.types;
+        let name = self.member.resolve_rust_name();
+        let (return_type, target, arguments) = self.parts();
+        let (target_name, target_type) = self.target(target);
+        let args_names = arguments.iter().map(|a| &a.0);
+        let args_types = arguments.iter().map(|a| self.render_argument_type(&a.1));
+        let return_type = types.render_rust_type(&return_type);
+
+        quote! {
+            fn #name(
+                ctx: ::bind_java::Context,
+                #target_name: #target_type,
+                #(#args_names: #args_types),*
+            ) -> ::bind_java::Result<#return_type>;
+        }
+    }
+
+    fn build_shim(&self, fn_name: &Ident, generic_list: TokenStream, implementor: TokenStream) -> TokenStream {
+        let types = self.class_ctx.types;
+        let name = self.member.resolve_rust_name();
+        let (return_type, target, arguments) = self.parts();
+        let (target_name, target_type) = self.target(target);
+        let args_names = arguments.iter().map(|a| &a.0).collect::<Vec<_>>();
+        let args_jni_types = arguments.iter().map(|a| types.render_type(&a.1)).collect::<Vec<_>>();
+        let args_types = arguments.iter().map(|a| self.render_argument_type(&a.1));
+        let jni_return_type = types.render_type(&return_type);
+        let panic_exception = &self.class_ctx.panic_exception;
+
+        quote! {
+            extern "system" fn #fn_name #generic_list (
+                ctx: ::bind_java::Context,
+                #target_name: #target_type,
+                #(#args_names: #args_jni_types),*
+            ) -> #jni_return_type {
+                unsafe {
+                    ::bind_java::native_call(ctx, #panic_exception, || {
+                        let result = #implementor::#name(
+                            ctx,
+                            #target_name,
+                            #(<#args_types as ::bind_java::FromJava<#args_jni_types>>::from_java(#args_names, ctx)?),*
+                        )?;
+
+                        ::bind_java::IntoJava::<#jni_return_type>::into_java(result, ctx)
+                    })
+                }
+            }
+        }
+    }
+
+    pub fn shim(&self, natives: &Ident) -> TokenStream {
+        self.build_shim(&self.member.resolve_rust_name(), quote! { <N: #natives> }, quote! { N })
+    }
+
+    pub fn export(&self, class_name: &str, natives: &Ident, implementor: &syn::Path, overloaded: bool) -> TokenStream {
+        let Member::Method { name, .. } = self.member else {
+            unreachable!("native members are always methods")
+        };
+
+        let (_, _, arguments) = self.parts();
+        let arguments_signature = arguments.iter().map(|a| a.1.to_signature()).collect::<String>();
+        let export_name = mangle::export_name(
+            &class_name.replace('.', "/"),
+            &name.to_string(),
+            overloaded.then_some(arguments_signature.as_str()),
+        );
+        let shim = self.build_shim(
+            &Ident::new(&export_name, name.span()),
+            quote! {},
+            quote! { <#implementor as #natives> },
+        );
+
+        quote! {
+            #[no_mangle]
+            #[allow(non_snake_case)]
+            pub #shim
+        }
+    }
+
+    pub fn java_name(&self) -> String {
+        match self.member {
+            Member::Method { name, .. } => name.to_string(),
+            _ => unreachable!("native members are always methods"),
+        }
+    }
+
+    pub fn entry(&self) -> TokenStream {
+        let Member::Method { name, .. } = self.member else {
+            unreachable!("native members are always methods")
+        };
+
+        let rs_name = self.member.resolve_rust_name();
+        let (return_type, _, arguments) = self.parts();
+        let method_name = name.to_string();
+        let signature = signature::method_signature(&return_type, arguments.into_iter().map(|a| a.1));
+
+        quote! {
+            ::bind_java::NativeMethod {
+                name: #method_name,
+                signature: #signature,
+                func: #rs_name::<N> as *const (),
+            }
+        }
+    }
+}
@@ -5,11 +5,12 @@ use crate::member::Member;
 
 pub struct StructForMember<'a> {
     member: &'a Member,
+    lazy: bool,
 }
 
 impl<'a> StructForMember<'a> {
-    pub fn new(member: &'a Member) -> Self {
-        StructForMember { member }
+    pub fn new(member: &'a Member, lazy: bool) -> Self {
+        StructForMember { member, lazy }
     }
 
     pub fn field_name(&self) -> Ident {
@@ -31,18 +32,24 @@ impl<'a> ToTokens for StructForMember<'a> {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let name = self.field_name();
 
-        let ts = match &self.member {
+        let id_type = match &self.member {
             Member::Constructor { .. } => {
-                quote! { #name: ::jni_sys::jmethodID }
+                quote! { ::bind_java::MethodId }
             }
             Member::Method { .. } => {
-                quote! { #name: ::jni_sys::jmethodID }
+                quote! { ::bind_java::MethodId }
             }
             Member::Field { .. } => {
-                quote! { #name: ::jni_sys::jfieldID }
+                quote! { ::bind_java::FieldId }
             }
         };
 
+        let ts = if self.lazy {
+            quote! { #name: ::bind_java::LazyId<#id_type> }
+        } else {
+            quote! { #name: #id_type }
+        };
+
         tokens.extend(ts);
     }
 }
@@ -1,6 +1,5 @@
 use syn::{
     parse::{Parse, ParseStream},
-    token::{Final, Static},
     Token,
 };
 
@@ -13,9 +12,9 @@ pub mod kw {
 }
 
 pub enum Modifier {
-    Static(Static),
-    Final(Final),
-    Native(kw::native),
+    Static,
+    Final,
+    Native,
 }
 
 impl Repeatable for Modifier {
@@ -28,11 +27,17 @@ impl Parse for Modifier {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let lookahead = input.lookahead1();
         if lookahead.peek(Token![static]) {
-            Ok(Modifier::Static(input.parse()?))
+            input.parse::<Token![static]>()?;
+
+            Ok(Modifier::Static)
         } else if lookahead.peek(Token![final]) {
-            Ok(Modifier::Final(input.parse()?))
+            input.parse::<Token![final]>()?;
+
+            Ok(Modifier::Final)
         } else if lookahead.peek(kw::native) {
-            Ok(Modifier::Native(input.parse()?))
+            input.parse::<kw::native>()?;
+
+            Ok(Modifier::Native)
         } else {
             Err(lookahead.error())
         }
@@ -47,14 +52,14 @@ pub trait ModifiersExt {
 
 impl ModifiersExt for Repeat<Modifier> {
     fn is_static(&self) -> bool {
-        self.values().iter().any(|m| matches!(m, Modifier::Static(_)))
+        self.values().iter().any(|m| matches!(m, Modifier::Static))
     }
 
     fn is_final(&self) -> bool {
-        self.values().iter().any(|m| matches!(m, Modifier::Final(_)))
+        self.values().iter().any(|m| matches!(m, Modifier::Final))
     }
 
     fn is_native(&self) -> bool {
-        self.values().iter().any(|m| matches!(m, Modifier::Native(_)))
+        self.values().iter().any(|m| matches!(m, Modifier::Native))
     }
 }
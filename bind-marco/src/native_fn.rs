@@ -0,0 +1,113 @@
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{
+    meta::ParseNestedMeta, spanned::Spanned, FnArg, GenericArgument, ItemFn, LitStr, PathArguments, ReturnType, Type, Visibility,
+};
+
+#[derive(Default)]
+pub struct NativeFnArgs {
+    exception: Option<LitStr>,
+}
+
+impl NativeFnArgs {
+    pub fn parse(&mut self, meta: ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("exception") {
+            self.exception = Some(meta.value()?.parse()?);
+
+            Ok(())
+        } else {
+            Err(meta.error("unsupported native property."))
+        }
+    }
+}
+
+// only `Result<T>` and `bind_java::Result<T>` are unwrapped, other result types are returned as they are.
+fn result_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    if path.qself.is_some() {
+        return None;
+    }
+
+    let segments = path.path.segments.iter().map(|s| s.ident.to_string()).collect::<Vec<_>>();
+    if segments != ["Result"] && segments != ["bind_java", "Result"] {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(arguments) = &path.path.segments.last()?.arguments else {
+        return None;
+    };
+    if arguments.args.len() != 1 {
+        return None;
+    }
+
+    match arguments.args.first()? {
+        GenericArgument::Type(ty) => Some(ty),
+        _ => None,
+    }
+}
+
+pub fn expand(args: NativeFnArgs, func: ItemFn) -> syn::Result<TokenStream> {
+    let ItemFn { attrs, vis, sig, .. } = &func;
+    let name = &sig.ident;
+    let exception = args
+        .exception
+        .map(|e| e.value().replace('.', "/"))
+        .unwrap_or_else(|| "java/lang/RuntimeException".to_owned());
+
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(sig.generics.span(), "native functions can not be generic."));
+    }
+
+    let mut inputs = sig.inputs.iter();
+    let ctx_type = match inputs.next() {
+        Some(FnArg::Typed(arg)) => &arg.ty,
+        Some(arg) => return Err(syn::Error::new(arg.span(), "native functions can not take self.")),
+        None => return Err(syn::Error::new(sig.span(), "native functions must take the context first.")),
+    };
+
+    let mut args_names = Vec::new();
+    let mut args_types = Vec::new();
+    for (idx, arg) in inputs.enumerate() {
+        let FnArg::Typed(arg) = arg else {
+            return Err(syn::Error::new(arg.span(), "native functions can not take self."));
+        };
+
+        args_names.push(format_ident!("arg{}", idx, span = Span::mixed_site()));
+        args_types.push(&arg.ty);
+    }
+
+    let (return_type, call) = match &sig.output {
+        ReturnType::Default => (quote! { () }, quote! { #name(ctx, #(#args_names),*) }),
+        ReturnType::Type(_, ty) => match result_inner(ty) {
+            Some(inner) => (quote! { #inner }, quote! { #name(ctx, #(#args_names),*)? }),
+            None => (quote! { #ty }, quote! { #name(ctx, #(#args_names),*) }),
+        },
+    };
+    let ctx = quote! { ctx };
+    let inner = ItemFn {
+        attrs: Vec::new(),
+        vis: Visibility::Inherited,
+        ..func.clone()
+    };
+
+    Ok(quote! {
+        #(#attrs)*
+        #vis extern "system" fn #name(
+            #ctx: #ctx_type,
+            #(#args_names: <#args_types as ::bind_java::NativeType>::Raw),*
+        ) -> <#return_type as ::bind_java::NativeType>::Raw {
+            #inner
+
+            unsafe {
+                ::bind_java::native_call(#ctx, #exception, || {
+                    #(let #args_names = <#args_types as ::bind_java::FromJava<_>>::from_java(#args_names, #ctx)?;)*
+                    let result: #return_type = #call;
+
+                    ::bind_java::IntoJava::<<#return_type as ::bind_java::NativeType>::Raw>::into_java(result, #ctx)
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,51 @@
+use syn::{
+    parse::{Parse, ParseStream},
+    Token,
+};
+
+use crate::{
+    repeat::Repeatable,
+    types::{Type, TypeName},
+};
+
+mod kw {
+    use syn::custom_keyword;
+
+    custom_keyword!(map);
+}
+
+pub struct TypeMap {
+    _map: kw::map,
+    java_type: TypeName,
+    _arrow: Token![=>],
+    rust_type: syn::Type,
+    _semi: Token![;],
+}
+
+impl Parse for TypeMap {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        Ok(TypeMap {
+            _map: input.parse()?,
+            java_type: input.parse()?,
+            _arrow: input.parse()?,
+            rust_type: input.parse()?,
+            _semi: input.parse()?,
+        })
+    }
+}
+
+impl Repeatable for TypeMap {
+    fn should_continue(input: ParseStream) -> bool {
+        input.peek(kw::map)
+    }
+}
+
+impl TypeMap {
+    pub fn java_type(&self) -> Type {
+        self.java_type.to_type()
+    }
+
+    pub fn rust_type(&self) -> &syn::Type {
+        &self.rust_type
+    }
+}
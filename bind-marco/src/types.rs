@@ -1,6 +1,12 @@
-use std::fmt::{Display, Formatter};
+use std::{
+    cell::RefCell,
+    collections::{BTreeSet, HashMap},
+    fmt::{Display, Formatter},
+};
 
+use convert_case::{Case, Casing};
 use proc_macro2::{Delimiter, Ident, TokenStream};
+use quote::{format_ident, quote, ToTokens};
 use syn::{
     bracketed,
     parse::{Parse, ParseStream},
@@ -10,8 +16,8 @@ use syn::{
 
 enum ClassNameSegment {
     Ident(Ident),
-    Dot(Token![.]),
-    Dollar(Token![$]),
+    Dot,
+    Dollar,
 }
 
 pub struct ClassName {
@@ -26,9 +32,11 @@ impl Parse for ClassName {
             segments.push(ClassNameSegment::Ident(input.parse()?));
 
             if input.peek(Token![.]) {
-                segments.push(ClassNameSegment::Dot(input.parse()?));
+                input.parse::<Token![.]>()?;
+                segments.push(ClassNameSegment::Dot);
             } else if input.peek(Token![$]) {
-                segments.push(ClassNameSegment::Dollar(input.parse()?));
+                input.parse::<Token![$]>()?;
+                segments.push(ClassNameSegment::Dollar);
             } else {
                 break;
             }
@@ -45,8 +53,8 @@ impl ClassName {
         for x in &self.segments {
             match x {
                 ClassNameSegment::Ident(ident) => result.push_str(&ident.to_string()),
-                ClassNameSegment::Dot(_) => result.push('.'),
-                ClassNameSegment::Dollar(_) => result.push('$'),
+                ClassNameSegment::Dot => result.push('.'),
+                ClassNameSegment::Dollar => result.push('$'),
             }
         }
 
@@ -66,7 +74,7 @@ impl Parse for TypeName {
             array_marks: {
                 let mut marks = Vec::<Bracket>::new();
 
-                while let Some(_) = input.cursor().group(Delimiter::Bracket) {
+                while input.cursor().group(Delimiter::Bracket).is_some() {
                     let _content;
                     marks.push(bracketed!(_content in input));
                 }
@@ -139,36 +147,35 @@ impl Type {
         }
     }
 
-    pub fn to_jni_type(&self) -> &'static str {
+    pub fn is_primitive_type(&self) -> bool {
+        matches!(
+            self,
+            Type::Boolean | Type::Byte | Type::Char | Type::Short | Type::Int | Type::Long | Type::Float | Type::Double
+        )
+    }
+
+    pub fn is_constant_type(&self) -> bool {
+        !matches!(self, Type::Void | Type::Class | Type::Object(_) | Type::Array(_))
+    }
+
+    pub fn to_raw_type(&self) -> &'static str {
         match self {
             Type::Void => "()",
-            Type::Boolean => "::jni_sys::jboolean",
-            Type::Byte => "::jni_sys::jbyte",
-            Type::Char => "::jni_sys::jchar",
-            Type::Short => "::jni_sys::jshort",
-            Type::Int => "::jni_sys::jint",
-            Type::Long => "::jni_sys::jlong",
-            Type::Float => "::jni_sys::jfloat",
-            Type::Double => "::jni_sys::jdouble",
-            Type::String => "::jni_sys::jstring",
-            Type::Class => "::jni_sys::jclass",
-            Type::Object(_) => "::jni_sys::jobject",
-            Type::Array(inner) => match inner.as_ref() {
-                Type::Boolean => "::jni_sys::jbooleanArray",
-                Type::Byte => "::jni_sys::jbyteArray",
-                Type::Char => "::jni_sys::jcharArray",
-                Type::Short => "::jni_sys::jshortArray",
-                Type::Int => "::jni_sys::jintArray",
-                Type::Long => "::jni_sys::jlongArray",
-                Type::Float => "::jni_sys::jfloatArray",
-                Type::Double => "::jni_sys::jdoubleArray",
-                _ => "::jni_sys::jobjectArray",
-            },
+            Type::Boolean => "bool",
+            Type::Byte => "i8",
+            Type::Char => "u16",
+            Type::Short => "i16",
+            Type::Int => "i32",
+            Type::Long => "i64",
+            Type::Float => "f32",
+            Type::Double => "f64",
+            Type::Class => "::bind_java::Class",
+            Type::String | Type::Object(_) | Type::Array(_) => "::bind_java::Object",
         }
     }
 
-    pub fn render_jni_type(&self) -> TokenStream {
-        self.to_jni_type().parse().unwrap()
+    pub fn render_raw_type(&self) -> TokenStream {
+        self.to_raw_type().parse().unwrap()
     }
 }
 
@@ -195,3 +202,192 @@ impl Display for Type {
         }
     }
 }
+
+const BUILTIN_HANDLES: [(&str, &str); 4] = [
+    ("java.util.List", "::bind_java::ListRef"),
+    ("java.util.Map", "::bind_java::MapRef"),
+    ("java.util.Set", "::bind_java::SetRef"),
+    ("java.nio.ByteBuffer", "::bind_java::ByteBufferRef"),
+];
+
+pub struct TypeRegistry {
+    handles: HashMap<String, TokenStream>,
+    mappings: HashMap<String, syn::Type>,
+    mapped_elements: RefCell<BTreeSet<String>>,
+    element_scope: String,
+}
+
+impl Default for TypeRegistry {
+    fn default() -> Self {
+        TypeRegistry {
+            handles: BUILTIN_HANDLES
+                .iter()
+                .map(|(class_name, handle)| (class_name.to_string(), handle.parse().unwrap()))
+                .collect(),
+            mappings: HashMap::new(),
+            mapped_elements: RefCell::new(BTreeSet::new()),
+            element_scope: String::new(),
+        }
+    }
+}
+
+impl TypeRegistry {
+    pub fn register(&mut self, class_name: &str, handle: Ident) {
+        self.handles.insert(class_name.replace('/', "."), handle.to_token_stream());
+    }
+
+    pub fn map(&mut self, java_type: &Type, rust_type: syn::Type) {
+        self.mappings.insert(java_type.to_string().replace('/', "."), rust_type);
+    }
+
+    fn mapped_type(&self, typ: &Type) -> Option<&syn::Type> {
+        self.mappings.get(&typ.to_string().replace('/', "."))
+    }
+
+    // markers are emitted next to the bindings, so they are prefixed to keep separate blocks from colliding.
+    pub fn scope_elements(&mut self, scope: &Ident) {
+        self.element_scope = scope.to_string();
+    }
+
+    fn mapped_element_name(&self, class_name: &str) -> Ident {
+        format_ident!(
+            "{}{}Element",
+            self.element_scope,
+            class_name.replace(['.', '$'], "_").to_case(Case::Pascal)
+        )
+    }
+
+    // mapped classes have no handle, arrays of them use a marker that only carries the class signature.
+    fn mapped_element(&self, class_name: &str) -> Option<TokenStream> {
+        if !self.mappings.contains_key(class_name) {
+            return None;
+        }
+
+        self.mapped_elements.borrow_mut().insert(class_name.to_owned());
+
+        Some(self.mapped_element_name(class_name).to_token_stream())
+    }
+
+    pub fn render_mapped_elements(&self) -> TokenStream {
+        let mapped_elements = self.mapped_elements.borrow();
+        let elements = mapped_elements.iter().map(|class_name| {
+            let name = self.mapped_element_name(class_name);
+            let signature = format!("L{};", class_name.replace('.', "/"));
+
+            quote! {
+                enum #name {}
+
+                unsafe impl ::bind_java::ArrayElement for #name {
+                    type Raw = ::bind_java::Object;
+
+                    fn signature() -> ::std::string::String {
+                        #signature.to_owned()
+                    }
+                }
+            }
+        });
+
+        quote! { #(#elements)* }
+    }
+
+    pub fn render_argument_type(&self, typ: &Type) -> TokenStream {
+        match self.mapped_type(typ) {
+            Some(rust_type) => rust_type.to_token_stream(),
+            None => {
+                let jni_type = self.render_type(typ);
+
+                quote! { impl ::bind_java::IntoJava<#jni_type> }
+            }
+        }
+    }
+
+    fn render_array_element(&self, typ: &Type) -> Option<TokenStream> {
+        match typ {
+            Type::String => Some(quote! { ::std::string::String }),
+            Type::Object(name) => {
+                let name = name.replace('/', ".");
+                let boxed = match name.as_str() {
+                    "java.lang.Object" => quote! { ::bind_java::Object },
+                    "java.lang.Boolean" => quote! { bool },
+                    "java.lang.Byte" => quote! { i8 },
+                    "java.lang.Character" => quote! { char },
+                    "java.lang.Short" => quote! { i16 },
+                    "java.lang.Integer" => quote! { i32 },
+                    "java.lang.Long" => quote! { i64 },
+                    "java.lang.Float" => quote! { f32 },
+                    "java.lang.Double" => quote! { f64 },
+                    _ => return self.handles.get(&name).cloned().or_else(|| self.mapped_element(&name)),
+                };
+
+                Some(boxed)
+            }
+            Type::Array(inner) if inner.is_primitive_type() => {
+                let inner = inner.render_raw_type();
+
+                Some(quote! { ::std::vec::Vec<#inner> })
+            }
+            Type::Array(inner) => {
+                let element = self.render_array_element(inner)?;
+
+                Some(quote! { ::bind_java::ObjectArrayRef<#element> })
+            }
+            _ => None,
+        }
+    }
+
+    pub fn render_type(&self, typ: &Type) -> TokenStream {
+        match typ {
+            Type::Object(name) => match self.handles.get(&name.replace('/', ".")) {
+                Some(handle) => handle.clone(),
+                None => typ.render_raw_type(),
+            },
+            Type::Array(inner) => match self.render_array_element(inner) {
+                Some(element) if !inner.is_primitive_type() => {
+                    quote! { ::bind_java::ObjectArrayRef<#element> }
+                }
+                _ => typ.render_raw_type(),
+            },
+            _ => typ.render_raw_type(),
+        }
+    }
+
+    pub fn render_rust_type(&self, typ: &Type) -> TokenStream {
+        if let Some(rust_type) = self.mapped_type(typ) {
+            return rust_type.to_token_stream();
+        }
+
+        match typ {
+            Type::Void => quote! { () },
+            Type::Boolean => quote! { bool },
+            Type::Byte => quote! { i8 },
+            Type::Char => quote! { char },
+            Type::Short => quote! { i16 },
+            Type::Int => quote! { i32 },
+            Type::Long => quote! { i64 },
+            Type::Float => quote! { f32 },
+            Type::Double => quote! { f64 },
+            Type::String => quote! { ::std::string::String },
+            Type::Class => quote! { ::bind_java::Class },
+            Type::Object(_) => match self.handles.get(&typ.to_string().replace('/', ".")) {
+                Some(handle) => handle.clone(),
+                None => quote! { ::bind_java::Object },
+            },
+            Type::Array(inner) => match inner.as_ref() {
+                Type::Object(_) | Type::Class | Type::Array(_) if self.render_array_element(inner).is_none() => {
+                    typ.render_raw_type()
+                }
+                // java char arrays may hold surrogate pairs, so they stay utf-16 units.
+                inner if inner.is_primitive_type() => {
+                    let inner = inner.render_raw_type();
+
+                    quote! { ::std::vec::Vec<#inner> }
+                }
+                inner => {
+                    let inner = self.render_rust_type(inner);
+
+                    quote! { ::std::vec::Vec<#inner> }
+                }
+            },
+        }
+    }
+}
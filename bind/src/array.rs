@@ -0,0 +1,177 @@
+use std::marker::PhantomData;
+
+use jni_sys::{
+    jboolean, jbooleanArray, jbyte, jbyteArray, jchar, jcharArray, jdouble, jdoubleArray, jfloat, jfloatArray, jint, jintArray,
+    jlong, jlongArray, jshort, jshortArray, jsize,
+};
+
+use crate::{
+    call, find_class, invoke_with_throwable, with_element_frame, with_pushed_frame, ByteBufferRef, Class, Context, FromJava,
+    IntoJava, ListRef, MapRef, Object, Reference, Result, SetRef,
+};
+
+/// # Safety
+///
+/// `signature` must describe the java type stored in the array and `Raw` a handle valid for it, elements
+/// read from the array are cast to `Raw` unchecked.
+pub unsafe trait ArrayElement {
+    type Raw: Reference;
+
+    fn signature() -> String;
+
+    fn element_class(ctx: Context) -> Result<Class> {
+        let signature = Self::signature();
+
+        match signature.strip_prefix('L').and_then(|s| s.strip_suffix(';')) {
+            Some(name) => find_class(ctx, name),
+            None => find_class(ctx, &signature),
+        }
+    }
+}
+
+macro_rules! element_impl {
+    ($typ:ty, $raw:ty, $signature:literal) => {
+        unsafe impl ArrayElement for $typ {
+            type Raw = $raw;
+
+            fn signature() -> String {
+                $signature.to_owned()
+            }
+        }
+    };
+}
+
+element_impl!(Object, Object, "Ljava/lang/Object;");
+element_impl!(String, Object, "Ljava/lang/String;");
+element_impl!(bool, Object, "Ljava/lang/Boolean;");
+element_impl!(i8, Object, "Ljava/lang/Byte;");
+element_impl!(char, Object, "Ljava/lang/Character;");
+element_impl!(i16, Object, "Ljava/lang/Short;");
+element_impl!(i32, Object, "Ljava/lang/Integer;");
+element_impl!(i64, Object, "Ljava/lang/Long;");
+element_impl!(f32, Object, "Ljava/lang/Float;");
+element_impl!(f64, Object, "Ljava/lang/Double;");
+element_impl!(ListRef, ListRef, "Ljava/util/List;");
+element_impl!(MapRef, MapRef, "Ljava/util/Map;");
+element_impl!(SetRef, SetRef, "Ljava/util/Set;");
+element_impl!(ByteBufferRef, ByteBufferRef, "Ljava/nio/ByteBuffer;");
+element_impl!(Vec<jboolean>, jbooleanArray, "[Z");
+element_impl!(Vec<jbyte>, jbyteArray, "[B");
+element_impl!(Vec<jchar>, jcharArray, "[C");
+element_impl!(Vec<jshort>, jshortArray, "[S");
+element_impl!(Vec<jint>, jintArray, "[I");
+element_impl!(Vec<jlong>, jlongArray, "[J");
+element_impl!(Vec<jfloat>, jfloatArray, "[F");
+element_impl!(Vec<jdouble>, jdoubleArray, "[D");
+
+#[repr(transparent)]
+pub struct ObjectArrayRef<E> {
+    object: Object,
+    _element: PhantomData<fn() -> E>,
+}
+
+impl<E> Clone for ObjectArrayRef<E> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<E> Copy for ObjectArrayRef<E> {}
+
+unsafe impl<E> Reference for ObjectArrayRef<E> {
+    fn as_object(self) -> Object {
+        self.object
+    }
+
+    unsafe fn from_object(object: Object) -> Self {
+        ObjectArrayRef {
+            object,
+            _element: PhantomData,
+        }
+    }
+}
+
+impl<E> FromJava<ObjectArrayRef<E>> for Object {
+    unsafe fn from_java(value: ObjectArrayRef<E>, _: Context) -> Result<Self> {
+        Ok(value.object)
+    }
+}
+
+impl<E> From<ObjectArrayRef<E>> for Object {
+    fn from(value: ObjectArrayRef<E>) -> Self {
+        value.object
+    }
+}
+
+unsafe impl<E: ArrayElement> ArrayElement for ObjectArrayRef<E> {
+    type Raw = ObjectArrayRef<E>;
+
+    fn signature() -> String {
+        format!("[{}", E::signature())
+    }
+}
+
+impl<E: ArrayElement, T: FromJava<E::Raw>> FromJava<ObjectArrayRef<E>> for Vec<T> {
+    unsafe fn from_java(value: ObjectArrayRef<E>, ctx: Context) -> Result<Self> {
+        let length = call!(v1_1, ctx, GetArrayLength, value.object);
+
+        let result = (0..length)
+            .map(|idx| {
+                let element = with_element_frame(ctx, || {
+                    invoke_with_throwable(ctx, || call!(v1_1, ctx, GetObjectArrayElement, value.object, idx))
+                })?;
+
+                T::from_java(E::Raw::from_object(element), ctx)
+            })
+            .collect();
+
+        call!(v1_1, ctx, DeleteLocalRef, value.object);
+
+        result
+    }
+}
+
+fn new_object_array<E, T, I>(ctx: Context, elements: I) -> Result<ObjectArrayRef<E>>
+where
+    E: ArrayElement,
+    T: IntoJava<E::Raw>,
+    I: ExactSizeIterator<Item = T>,
+{
+    with_pushed_frame(ctx, 2, || unsafe {
+        let class = E::element_class(ctx)?;
+        let array = invoke_with_throwable(ctx, || {
+            call!(
+                v1_1,
+                ctx,
+                NewObjectArray,
+                elements.len() as jsize,
+                class,
+                std::ptr::null_mut()
+            )
+        })?;
+
+        for (idx, element) in elements.enumerate() {
+            with_element_frame(ctx, || {
+                let element = element.into_java(ctx)?;
+
+                invoke_with_throwable(ctx, || {
+                    call!(v1_1, ctx, SetObjectArrayElement, array, idx as jsize, element.as_object())
+                })
+            })?;
+        }
+
+        Ok(ObjectArrayRef::from_object(array))
+    })
+}
+
+impl<E: ArrayElement, T: IntoJava<E::Raw>> IntoJava<ObjectArrayRef<E>> for Vec<T> {
+    fn into_java(self, ctx: Context) -> Result<ObjectArrayRef<E>> {
+        new_object_array(ctx, self.into_iter())
+    }
+}
+
+impl<E: ArrayElement, T: Clone + IntoJava<E::Raw>> IntoJava<ObjectArrayRef<E>> for &[T] {
+    fn into_java(self, ctx: Context) -> Result<ObjectArrayRef<E>> {
+        new_object_array(ctx, self.iter().cloned())
+    }
+}
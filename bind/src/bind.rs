@@ -1,16 +1,67 @@
-use std::ffi::CString;
+use std::{ffi::CString, fmt::Debug, ptr::null_mut};
 
-use jni_sys::{jfieldID, jmethodID, JNINativeMethod};
+use jni_sys::{jint, jsize, JNINativeMethod};
 
-use crate::{call, invoke_with_throwable, Class, Context, Result};
+use crate::{
+    call, get_static_field, invoke_with_throwable, is_instance_of, load_with_fallback, new_throwable, Class, ClassLoader,
+    Context, FieldId, FromJava, InvokeType, MethodId, Result, Throwable,
+};
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn find_class(ctx: Context, internal_name: &str) -> Result<Class> {
     let name = CString::new(internal_name).unwrap();
 
-    unsafe { invoke_with_throwable(ctx, || call!(v1_1, ctx, FindClass, name.as_ptr())) }
+    let throwable = match unsafe { invoke_with_throwable(ctx, || call!(v1_1, ctx, FindClass, name.as_ptr())) } {
+        Ok(class) => return Ok(class),
+        Err(throwable) => throwable,
+    };
+
+    if unsafe { is_class_not_found(ctx, throwable) } {
+        if let Some(class) = load_with_fallback(ctx, internal_name) {
+            unsafe { call!(v1_1, ctx, DeleteLocalRef, throwable) };
+
+            return Ok(class);
+        }
+    }
+
+    Err(throwable)
+}
+
+unsafe fn is_class_not_found(ctx: Context, throwable: Throwable) -> bool {
+    [c"java/lang/NoClassDefFoundError", c"java/lang/ClassNotFoundException"]
+        .iter()
+        .any(|name| {
+            let class = call!(v1_1, ctx, FindClass, name.as_ptr());
+            let matched = !class.is_null() && is_instance_of(ctx, throwable, class);
+
+            call!(v1_1, ctx, DeleteLocalRef, class);
+
+            matched
+        })
 }
 
-pub fn find_method(ctx: Context, class: Class, name: &str, signature: &str) -> Result<jmethodID> {
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn define_class(ctx: Context, name: &str, loader: Option<&dyn ClassLoader>, bytes: &[u8]) -> Result<Class> {
+    let name = CString::new(name.replace('.', "/")).unwrap();
+    let loader = loader.map_or(null_mut(), |l| l.object());
+
+    unsafe {
+        invoke_with_throwable(ctx, || {
+            call!(
+                v1_1,
+                ctx,
+                DefineClass,
+                name.as_ptr(),
+                loader,
+                bytes.as_ptr().cast(),
+                bytes.len() as jsize
+            )
+        })
+    }
+}
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn find_method(ctx: Context, class: Class, name: &str, signature: &str) -> Result<MethodId> {
     let name = CString::new(name).unwrap();
     let signature = CString::new(signature).unwrap();
 
@@ -18,10 +69,12 @@ pub fn find_method(ctx: Context, class: Class, name: &str, signature: &str) -> R
         invoke_with_throwable(ctx, || {
             call!(v1_1, ctx, GetMethodID, class, name.as_ptr(), signature.as_ptr())
         })
+        .map(|id| MethodId::from_raw(id))
     }
 }
 
-pub fn find_static_method(ctx: Context, class: Class, name: &str, signature: &str) -> Result<jmethodID> {
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn find_static_method(ctx: Context, class: Class, name: &str, signature: &str) -> Result<MethodId> {
     let name = CString::new(name).unwrap();
     let signature = CString::new(signature).unwrap();
 
@@ -29,17 +82,23 @@ pub fn find_static_method(ctx: Context, class: Class, name: &str, signature: &st
         invoke_with_throwable(ctx, || {
             call!(v1_1, ctx, GetStaticMethodID, class, name.as_ptr(), signature.as_ptr())
         })
+        .map(|id| MethodId::from_raw(id))
     }
 }
 
-pub fn find_field(ctx: Context, class: Class, name: &str, signature: &str) -> Result<jfieldID> {
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn find_field(ctx: Context, class: Class, name: &str, signature: &str) -> Result<FieldId> {
     let name = CString::new(name).unwrap();
     let signature = CString::new(signature).unwrap();
 
-    unsafe { invoke_with_throwable(ctx, || call!(v1_1, ctx, GetFieldID, class, name.as_ptr(), signature.as_ptr())) }
+    unsafe {
+        invoke_with_throwable(ctx, || call!(v1_1, ctx, GetFieldID, class, name.as_ptr(), signature.as_ptr()))
+            .map(|id| FieldId::from_raw(id))
+    }
 }
 
-pub fn find_static_field(ctx: Context, class: Class, name: &str, signature: &str) -> Result<jfieldID> {
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn find_static_field(ctx: Context, class: Class, name: &str, signature: &str) -> Result<FieldId> {
     let name = CString::new(name).unwrap();
     let signature = CString::new(signature).unwrap();
 
@@ -47,22 +106,66 @@ pub fn find_static_field(ctx: Context, class: Class, name: &str, signature: &str
         invoke_with_throwable(ctx, || {
             call!(v1_1, ctx, GetStaticFieldID, class, name.as_ptr(), signature.as_ptr())
         })
+        .map(|id| FieldId::from_raw(id))
     }
 }
 
+pub struct NativeMethod<'a> {
+    pub name: &'a str,
+    pub signature: &'a str,
+    pub func: *const (),
+}
+
 pub fn register_native_method(ctx: Context, class: Class, name: &str, signature: &str, func: *const ()) -> Result<()> {
-    unsafe {
-        let name = CString::new(name).unwrap();
-        let signature = CString::new(signature).unwrap();
+    register_native_methods(ctx, class, &[NativeMethod { name, signature, func }])
+}
 
-        let m = JNINativeMethod {
-            name: name.as_ptr().cast_mut(),
-            signature: signature.as_ptr().cast_mut(),
-            fnPtr: func.cast_mut().cast(),
-        };
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn register_native_methods(ctx: Context, class: Class, methods: &[NativeMethod]) -> Result<()> {
+    unsafe {
+        let names = methods
+            .iter()
+            .map(|m| (CString::new(m.name).unwrap(), CString::new(m.signature).unwrap()))
+            .collect::<Vec<_>>();
+
+        let table = methods
+            .iter()
+            .zip(&names)
+            .map(|(m, (name, signature))| JNINativeMethod {
+                name: name.as_ptr().cast_mut(),
+                signature: signature.as_ptr().cast_mut(),
+                fnPtr: m.func.cast_mut().cast(),
+            })
+            .collect::<Vec<_>>();
 
-        invoke_with_throwable(ctx, || call!(v1_1, ctx, RegisterNatives, class, &m, 1))?;
+        invoke_with_throwable(ctx, || {
+            call!(v1_1, ctx, RegisterNatives, class, table.as_ptr(), table.len() as jint)
+        })?;
     }
 
     Ok(())
 }
+
+/// # Safety
+///
+/// `ctx` must be the env of the current thread and `signature` must describe the java type `T`.
+pub unsafe fn check_constant_field<T: InvokeType, V: FromJava<T> + PartialEq<E>, E: Debug>(
+    ctx: Context,
+    class: Class,
+    name: &str,
+    signature: &str,
+    expected: E,
+) -> Result<()> {
+    let field = find_static_field(ctx, class, name, signature)?;
+    let value: V = get_static_field::<T, V>(ctx, class, field)?;
+
+    if value == expected {
+        Ok(())
+    } else {
+        Err(new_throwable(
+            ctx,
+            "java/lang/IncompatibleClassChangeError",
+            &format!("constant field {} no longer matches {:?}", name, expected),
+        ))
+    }
+}
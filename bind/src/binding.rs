@@ -1,7 +1,21 @@
-use crate::{Class, ClassLoader, Context, Result};
+use std::{
+    mem::ManuallyDrop,
+    ops::Deref,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use crate::{call, Class, ClassLoader, Context, GlobalRef, Object, Result, WeakRef};
 
 pub trait ClassBinding: Sized {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `class` a live reference to the class bound by `Self`.
     unsafe fn bind(ctx: Context, class: Class) -> Result<Self>;
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and the binding must not be used afterwards.
+    unsafe fn unbind(self, _ctx: Context) {}
 }
 
 pub trait WithClass {
@@ -9,3 +23,325 @@ pub trait WithClass {
 
     fn find_class(ctx: Context, loader: Option<&dyn ClassLoader>) -> Result<Class>;
 }
+
+pub trait BindingRef<T> {
+    fn class(&self) -> Class;
+
+    fn binding(&self) -> &T;
+}
+
+pub struct Bound<T> {
+    class: GlobalRef,
+    binding: T,
+}
+
+impl<T: ClassBinding> Bound<T> {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `class` a live reference to the class bound by `T`.
+    pub unsafe fn new(ctx: Context, class: Class) -> Result<Self> {
+        let class = GlobalRef::new(ctx, class)?;
+
+        match T::bind(ctx, class.as_object()) {
+            Ok(binding) => Ok(Bound { class, binding }),
+            Err(throwable) => {
+                class.delete(ctx);
+
+                Err(throwable)
+            }
+        }
+    }
+}
+
+impl<T> Bound<T> {
+    pub fn class(&self) -> Class {
+        self.class.as_object()
+    }
+}
+
+impl<T: ClassBinding> Bound<T> {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and no copy of the class or of the ids in the binding may be
+    /// used afterwards.
+    pub unsafe fn delete(self, ctx: Context) {
+        self.binding.unbind(ctx);
+        self.class.delete(ctx)
+    }
+}
+
+impl<T> BindingRef<T> for Bound<T> {
+    fn class(&self) -> Class {
+        self.class.as_object()
+    }
+
+    fn binding(&self) -> &T {
+        &self.binding
+    }
+}
+
+impl<T> Deref for Bound<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.binding
+    }
+}
+
+pub struct BindingCell<T> {
+    bound: OnceLock<Bound<T>>,
+}
+
+impl<T> BindingCell<T> {
+    pub const fn new() -> Self {
+        BindingCell { bound: OnceLock::new() }
+    }
+}
+
+impl<T: ClassBinding + WithClass> BindingCell<T> {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn get_or_bind(&self, ctx: Context) -> Result<&Bound<T>> {
+        if let Some(bound) = self.bound.get() {
+            return Ok(bound);
+        }
+
+        let class = T::find_class(ctx, None)?;
+        let bound = unsafe { Bound::<T>::new(ctx, class) };
+
+        unsafe { call!(v1_1, ctx, DeleteLocalRef, class) };
+
+        if let Err(bound) = self.bound.set(bound?) {
+            unsafe { bound.delete(ctx) };
+        }
+
+        Ok(self.bound.get().unwrap())
+    }
+}
+
+impl<T> Default for BindingCell<T> {
+    fn default() -> Self {
+        BindingCell::new()
+    }
+}
+
+// owns a local reference to the class, released when dropped on the thread that created it.
+pub struct LoaderBound<T: ClassBinding> {
+    ctx: Context,
+    class: Class,
+    binding: ManuallyDrop<Arc<T>>,
+}
+
+impl<T: ClassBinding> LoaderBound<T> {
+    pub fn class(&self) -> Class {
+        self.class
+    }
+}
+
+impl<T: ClassBinding> Drop for LoaderBound<T> {
+    fn drop(&mut self) {
+        unsafe {
+            call!(v1_1, self.ctx, DeleteLocalRef, self.class);
+
+            // the cache entry may have been evicted while this was alive, the last owner unbinds.
+            if let Some(binding) = Arc::into_inner(ManuallyDrop::take(&mut self.binding)) {
+                binding.unbind(self.ctx);
+            }
+        }
+    }
+}
+
+impl<T: ClassBinding> BindingRef<T> for LoaderBound<T> {
+    fn class(&self) -> Class {
+        self.class
+    }
+
+    fn binding(&self) -> &T {
+        &self.binding
+    }
+}
+
+impl<T: ClassBinding> Deref for LoaderBound<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.binding
+    }
+}
+
+struct LoaderEntry<T> {
+    loader: WeakRef,
+    class: WeakRef,
+    binding: Arc<T>,
+}
+
+impl<T: ClassBinding> LoaderEntry<T> {
+    unsafe fn delete(self, ctx: Context) {
+        self.loader.delete(ctx);
+        self.class.delete(ctx);
+
+        // bindings still handed out are unbound by the last LoaderBound dropped.
+        if let Some(binding) = Arc::into_inner(self.binding) {
+            binding.unbind(ctx);
+        }
+    }
+}
+
+pub struct LoaderCache<T> {
+    entries: Mutex<Vec<LoaderEntry<T>>>,
+}
+
+impl<T> LoaderCache<T> {
+    pub const fn new() -> Self {
+        LoaderCache {
+            entries: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<T: ClassBinding> LoaderCache<T> {
+    unsafe fn find(&self, ctx: Context, key: Object) -> Option<LoaderBound<T>> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let mut idx = 0;
+        while idx < entries.len() {
+            if entries[idx].loader.is_collected(ctx) || entries[idx].class.is_collected(ctx) {
+                entries.remove(idx).delete(ctx);
+            } else {
+                idx += 1;
+            }
+        }
+
+        entries.iter().find(|e| e.loader.is_same_object(ctx, key)).and_then(|e| {
+            Some(LoaderBound {
+                ctx,
+                class: e.class.upgrade(ctx)?,
+                binding: ManuallyDrop::new(e.binding.clone()),
+            })
+        })
+    }
+}
+
+impl<T: ClassBinding> LoaderCache<T> {
+    // the class bound through the most recent loader that is still alive, as a new local reference.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn latest_class(&self, ctx: Context) -> Option<Class> {
+        let entries = self.entries.lock().unwrap();
+
+        entries.iter().rev().find_map(|e| unsafe { e.class.upgrade(ctx) })
+    }
+}
+
+impl<T: ClassBinding + WithClass> LoaderCache<T> {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn get(&self, ctx: Context, loader: &dyn ClassLoader) -> Result<LoaderBound<T>> {
+        unsafe {
+            let key = loader.object();
+            if !key.is_null() {
+                if let Some(bound) = self.find(ctx, key) {
+                    return Ok(bound);
+                }
+            }
+
+            // resolve outside of the lock, class loading may call back into native code.
+            let class = T::find_class(ctx, Some(loader))?;
+
+            // loaders without a java object are keyed by the class they resolve instead.
+            let key = if key.is_null() {
+                if let Some(bound) = self.find(ctx, class) {
+                    call!(v1_1, ctx, DeleteLocalRef, class);
+
+                    return Ok(bound);
+                }
+
+                class
+            } else {
+                key
+            };
+
+            let binding = match T::bind(ctx, class) {
+                Ok(binding) => binding,
+                Err(throwable) => {
+                    call!(v1_1, ctx, DeleteLocalRef, class);
+
+                    return Err(throwable);
+                }
+            };
+
+            let mut entries = self.entries.lock().unwrap();
+
+            // another thread may have bound the same loader while this one was resolving.
+            if let Some(entry) = entries.iter().find(|e| e.loader.is_same_object(ctx, key)) {
+                binding.unbind(ctx);
+
+                return Ok(LoaderBound {
+                    ctx,
+                    class,
+                    binding: ManuallyDrop::new(entry.binding.clone()),
+                });
+            }
+
+            let bound = LoaderBound {
+                ctx,
+                class,
+                binding: ManuallyDrop::new(Arc::new(binding)),
+            };
+
+            entries.push(LoaderEntry {
+                loader: WeakRef::new(ctx, key)?,
+                class: WeakRef::new(ctx, class)?,
+                binding: (*bound.binding).clone(),
+            });
+
+            Ok(bound)
+        }
+    }
+}
+
+impl<T> Default for LoaderCache<T> {
+    fn default() -> Self {
+        LoaderCache::new()
+    }
+}
+
+macro_rules! builtin_binding {
+    (
+        $name:ident, $class_name:literal,
+        { $($field:ident: $method:literal $signature:literal),* $(,)? }
+        $(, static { $($static_field:ident: $static_method:literal $static_signature:literal),* $(,)? })?
+    ) => {
+        struct $name {
+            $($field: $crate::MethodId,)*
+            $($($static_field: $crate::MethodId,)*)?
+        }
+
+        impl $crate::ClassBinding for $name {
+            unsafe fn bind(ctx: $crate::Context, class: $crate::Class) -> $crate::Result<Self> {
+                Ok($name {
+                    $($field: $crate::find_method(ctx, class, $method, $signature)?,)*
+                    $($($static_field: $crate::find_static_method(ctx, class, $static_method, $static_signature)?,)*)?
+                })
+            }
+        }
+
+        impl $crate::WithClass for $name {
+            const CLASS_NAME: &'static str = $class_name;
+
+            fn find_class(ctx: $crate::Context, loader: Option<&dyn $crate::ClassLoader>) -> $crate::Result<$crate::Class> {
+                match loader {
+                    Some(loader) => loader.load_class(ctx, $class_name),
+                    None => $crate::find_class(ctx, &$class_name.replace('.', "/")),
+                }
+            }
+        }
+
+        impl $name {
+            fn bound(ctx: $crate::Context) -> $crate::Result<&'static $crate::Bound<Self>> {
+                static BOUND: $crate::BindingCell<$name> = $crate::BindingCell::new();
+
+                BOUND.get_or_bind(ctx)
+            }
+        }
+    };
+}
+
+pub(crate) use builtin_binding;
@@ -0,0 +1,78 @@
+use jni_sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort};
+
+use crate::{
+    binding::builtin_binding, call, call_method, call_static_method, new_throwable, Context, FromJava, IntoJava, IntoValue,
+    Object, Result,
+};
+
+unsafe fn unbox<T, F: FnOnce() -> Result<T>>(ctx: Context, value: Object, f: F) -> Result<T> {
+    if value.is_null() {
+        return Err(new_throwable(ctx, "java/lang/NullPointerException", "unboxing a null value"));
+    }
+
+    let result = f();
+
+    call!(v1_1, ctx, DeleteLocalRef, value);
+
+    result
+}
+
+macro_rules! boxed_impl {
+    ($rs_type:ty, $jni_type:ty, $binding:ident, $class_name:literal, $value:literal $value_sig:literal, $value_of_sig:literal) => {
+        builtin_binding!($binding, $class_name, {
+            value: $value $value_sig,
+        }, static {
+            value_of: "valueOf" $value_of_sig,
+        });
+
+        impl FromJava<Object> for $rs_type {
+            unsafe fn from_java(value: Object, ctx: Context) -> Result<Self> {
+                let binding = $binding::bound(ctx)?;
+
+                unbox(ctx, value, || call_method::<$jni_type, $rs_type>(ctx, value, binding.value, &[]))
+            }
+        }
+
+        impl IntoJava<Object> for $rs_type {
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
+            fn into_java(self, ctx: Context) -> Result<Object> {
+                let binding = $binding::bound(ctx)?;
+
+                unsafe { call_static_method::<Object, Object>(ctx, binding.class(), binding.value_of, &[self.into_value()]) }
+            }
+        }
+    };
+}
+
+boxed_impl!(bool, jboolean, JavaBoolean, "java.lang.Boolean", "booleanValue" "()Z", "(Z)Ljava/lang/Boolean;");
+boxed_impl!(i8, jbyte, JavaByte, "java.lang.Byte", "byteValue" "()B", "(B)Ljava/lang/Byte;");
+boxed_impl!(i16, jshort, JavaShort, "java.lang.Short", "shortValue" "()S", "(S)Ljava/lang/Short;");
+boxed_impl!(i32, jint, JavaInteger, "java.lang.Integer", "intValue" "()I", "(I)Ljava/lang/Integer;");
+boxed_impl!(i64, jlong, JavaLong, "java.lang.Long", "longValue" "()J", "(J)Ljava/lang/Long;");
+boxed_impl!(f32, jfloat, JavaFloat, "java.lang.Float", "floatValue" "()F", "(F)Ljava/lang/Float;");
+boxed_impl!(f64, jdouble, JavaDouble, "java.lang.Double", "doubleValue" "()D", "(D)Ljava/lang/Double;");
+
+builtin_binding!(JavaCharacter, "java.lang.Character", {
+    value: "charValue" "()C",
+}, static {
+    value_of: "valueOf" "(C)Ljava/lang/Character;",
+});
+
+impl FromJava<Object> for char {
+    unsafe fn from_java(value: Object, ctx: Context) -> Result<Self> {
+        let binding = JavaCharacter::bound(ctx)?;
+        let unit = unbox(ctx, value, || call_method::<jchar, jchar>(ctx, value, binding.value, &[]))?;
+
+        char::from_java(unit, ctx)
+    }
+}
+
+impl IntoJava<Object> for char {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn into_java(self, ctx: Context) -> Result<Object> {
+        let binding = JavaCharacter::bound(ctx)?;
+        let unit: jchar = self.into_java(ctx)?;
+
+        unsafe { call_static_method::<Object, Object>(ctx, binding.class(), binding.value_of, &[unit.into_value()]) }
+    }
+}
@@ -0,0 +1,236 @@
+use std::{ffi::c_void, marker::PhantomData, sync::Mutex};
+
+use jni_sys::{jint, jlong};
+
+use crate::{
+    binding::builtin_binding, call, call_method, invoke_with_throwable, new_throwable, reference::handle_impl, Context, FromJava,
+    IntoJava, Object, Reference, Result, WeakRef,
+};
+
+handle_impl!(ByteBufferRef);
+
+builtin_binding!(JavaByteBuffer, "java.nio.ByteBuffer", {
+    as_read_only_buffer: "asReadOnlyBuffer" "()Ljava/nio/ByteBuffer;",
+    position: "position" "()I",
+    limit: "limit" "()I",
+});
+
+struct OwnedBuffer {
+    buffer: WeakRef,
+    _data: Box<[u8]>,
+}
+
+struct OwnedBuffers {
+    buffers: Vec<OwnedBuffer>,
+    next_sweep: usize,
+}
+
+const MIN_SWEEP: usize = 64;
+
+// storage handed over to java stays here until the buffer wrapping it has been collected.
+static OWNED_BUFFERS: Mutex<OwnedBuffers> = Mutex::new(OwnedBuffers {
+    buffers: Vec::new(),
+    next_sweep: MIN_SWEEP,
+});
+
+impl OwnedBuffers {
+    unsafe fn sweep(&mut self, ctx: Context) -> usize {
+        let count = self.buffers.len();
+
+        let (collected, alive) = self.buffers.drain(..).partition::<Vec<_>, _>(|b| b.buffer.is_collected(ctx));
+        self.buffers = alive;
+        self.next_sweep = (self.buffers.len() * 2).max(MIN_SWEEP);
+
+        for buffer in collected {
+            buffer.buffer.delete(ctx);
+        }
+
+        count - self.buffers.len()
+    }
+}
+
+// `from_owned` only sweeps once the number of owned buffers has doubled, programs that stop
+// creating owned buffers must call this themselves to release the remaining storage.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn release_collected_buffers(ctx: Context) -> usize {
+    unsafe { OWNED_BUFFERS.lock().unwrap().sweep(ctx) }
+}
+
+unsafe fn new_direct_buffer(ctx: Context, address: *mut u8, capacity: usize) -> Result<ByteBufferRef> {
+    let buffer = invoke_with_throwable(ctx, || {
+        call!(v1_4, ctx, NewDirectByteBuffer, address.cast::<c_void>(), capacity as jlong)
+    })?;
+    if buffer.is_null() {
+        return Err(new_throwable(
+            ctx,
+            "java/lang/UnsupportedOperationException",
+            "direct buffers are not supported",
+        ));
+    }
+
+    Ok(ByteBufferRef(buffer))
+}
+
+impl ByteBufferRef {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn from_owned(ctx: Context, mut data: Box<[u8]>) -> Result<ByteBufferRef> {
+        unsafe {
+            let buffer = new_direct_buffer(ctx, data.as_mut_ptr(), data.len())?;
+            let weak = WeakRef::new(ctx, buffer.0)?;
+
+            let mut owned = OWNED_BUFFERS.lock().unwrap();
+            owned.buffers.push(OwnedBuffer {
+                buffer: weak,
+                _data: data,
+            });
+
+            if owned.buffers.len() >= owned.next_sweep {
+                owned.sweep(ctx);
+            }
+
+            Ok(buffer)
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread. java must not keep the returned buffer after `data` is gone,
+    /// prefer `BorrowedBuffer` which ties the buffer to the borrow.
+    pub unsafe fn from_slice(ctx: Context, data: &mut [u8]) -> Result<ByteBufferRef> {
+        new_direct_buffer(ctx, data.as_mut_ptr(), data.len())
+    }
+
+    /// # Safety
+    ///
+    /// Same as `from_slice`.
+    pub unsafe fn from_read_only_slice(ctx: Context, data: &[u8]) -> Result<ByteBufferRef> {
+        let b_buffer = JavaByteBuffer::bound(ctx)?;
+        let buffer = new_direct_buffer(ctx, data.as_ptr().cast_mut(), data.len())?;
+        let read_only = call_method::<Object, Object>(ctx, buffer.0, b_buffer.as_read_only_buffer, &[]);
+
+        call!(v1_1, ctx, DeleteLocalRef, buffer.0);
+
+        Ok(ByteBufferRef(read_only?))
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and the handle a live reference.
+    pub unsafe fn capacity(self, ctx: Context) -> Option<usize> {
+        let capacity = call!(v1_4, ctx, GetDirectBufferCapacity, self.0);
+
+        (capacity >= 0).then_some(capacity as usize)
+    }
+
+    /// # Safety
+    ///
+    /// Same as `capacity`.
+    pub unsafe fn address(self, ctx: Context) -> Option<*mut u8> {
+        let address = call!(v1_4, ctx, GetDirectBufferAddress, self.0);
+
+        (!address.is_null()).then_some(address.cast())
+    }
+
+    unsafe fn raw_parts(self, ctx: Context) -> Result<(*mut u8, usize)> {
+        match (self.address(ctx), self.capacity(ctx)) {
+            (Some(address), Some(capacity)) => Ok((address, capacity)),
+            _ => Err(new_throwable(
+                ctx,
+                "java/lang/IllegalArgumentException",
+                "not a direct buffer",
+            )),
+        }
+    }
+
+    /// # Safety
+    ///
+    /// Same as `capacity`. The slice covers the whole capacity, java must not write to or free the storage
+    /// while it is alive.
+    pub unsafe fn as_slice(&self, ctx: Context) -> Result<&[u8]> {
+        let (address, capacity) = self.raw_parts(ctx)?;
+
+        Ok(std::slice::from_raw_parts(address, capacity))
+    }
+
+    /// # Safety
+    ///
+    /// Same as `as_slice`, java must not read the storage either while the slice is alive.
+    pub unsafe fn as_mut_slice(&mut self, ctx: Context) -> Result<&mut [u8]> {
+        let (address, capacity) = self.raw_parts(ctx)?;
+
+        Ok(std::slice::from_raw_parts_mut(address, capacity))
+    }
+}
+
+// borrowed slices are only handed to java through this wrapper, java must not retain the buffer.
+pub struct BorrowedBuffer<'a> {
+    buffer: ByteBufferRef,
+    _data: PhantomData<&'a mut [u8]>,
+}
+
+impl<'a> BorrowedBuffer<'a> {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread. java must not retain the buffer past the borrow of `data`.
+    pub unsafe fn new(ctx: Context, data: &'a mut [u8]) -> Result<Self> {
+        Ok(BorrowedBuffer {
+            buffer: ByteBufferRef::from_slice(ctx, data)?,
+            _data: PhantomData,
+        })
+    }
+
+    /// # Safety
+    ///
+    /// Same as `new`.
+    pub unsafe fn read_only(ctx: Context, data: &'a [u8]) -> Result<Self> {
+        Ok(BorrowedBuffer {
+            buffer: ByteBufferRef::from_read_only_slice(ctx, data)?,
+            _data: PhantomData,
+        })
+    }
+
+    pub fn buffer(&self) -> ByteBufferRef {
+        self.buffer
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the thread that created the buffer.
+    pub unsafe fn delete(self, ctx: Context) {
+        call!(v1_1, ctx, DeleteLocalRef, self.buffer.0)
+    }
+}
+
+impl IntoJava<ByteBufferRef> for Vec<u8> {
+    fn into_java(self, ctx: Context) -> Result<ByteBufferRef> {
+        ByteBufferRef::from_owned(ctx, self.into_boxed_slice())
+    }
+}
+
+impl IntoJava<ByteBufferRef> for Box<[u8]> {
+    fn into_java(self, ctx: Context) -> Result<ByteBufferRef> {
+        ByteBufferRef::from_owned(ctx, self)
+    }
+}
+
+impl IntoJava<ByteBufferRef> for &BorrowedBuffer<'_> {
+    fn into_java(self, _: Context) -> Result<ByteBufferRef> {
+        Ok(self.buffer)
+    }
+}
+
+// only the remaining bytes between position and limit are copied, like a relative bulk get.
+impl FromJava<ByteBufferRef> for Vec<u8> {
+    unsafe fn from_java(value: ByteBufferRef, ctx: Context) -> Result<Self> {
+        let result = JavaByteBuffer::bound(ctx).and_then(|b_buffer| {
+            let position = call_method::<jint, jint>(ctx, value.0, b_buffer.position, &[])? as usize;
+            let limit = call_method::<jint, jint>(ctx, value.0, b_buffer.limit, &[])? as usize;
+
+            value.as_slice(ctx).map(|s| s[position..limit].to_vec())
+        });
+
+        call!(v1_1, ctx, DeleteLocalRef, value.0);
+
+        result
+    }
+}
@@ -0,0 +1,260 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::Hash,
+};
+
+use jni_sys::{jboolean, jint};
+
+use crate::{
+    binding::builtin_binding, call, call_method, new_object, reference::handle_impl, with_element_frame, with_pushed_frame,
+    Bound, Context, FromJava, IntoJava, IntoValue, MethodId, Object, Reference, Result,
+};
+
+handle_impl!(ListRef);
+handle_impl!(MapRef);
+handle_impl!(SetRef);
+
+builtin_binding!(JavaCollection, "java.util.Collection", {
+    size: "size" "()I",
+    add: "add" "(Ljava/lang/Object;)Z",
+    iterator: "iterator" "()Ljava/util/Iterator;",
+});
+
+builtin_binding!(JavaIterator, "java.util.Iterator", {
+    has_next: "hasNext" "()Z",
+    next: "next" "()Ljava/lang/Object;",
+});
+
+builtin_binding!(JavaMap, "java.util.Map", {
+    put: "put" "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+    entry_set: "entrySet" "()Ljava/util/Set;",
+});
+
+builtin_binding!(JavaMapEntry, "java.util.Map$Entry", {
+    get_key: "getKey" "()Ljava/lang/Object;",
+    get_value: "getValue" "()Ljava/lang/Object;",
+});
+
+builtin_binding!(JavaArrayList, "java.util.ArrayList", {
+    new: "<init>" "(I)V",
+});
+
+builtin_binding!(JavaHashSet, "java.util.HashSet", {
+    new: "<init>" "(I)V",
+});
+
+builtin_binding!(JavaLinkedHashSet, "java.util.LinkedHashSet", {
+    new: "<init>" "(I)V",
+});
+
+builtin_binding!(JavaHashMap, "java.util.HashMap", {
+    new: "<init>" "(I)V",
+});
+
+builtin_binding!(JavaLinkedHashMap, "java.util.LinkedHashMap", {
+    new: "<init>" "(I)V",
+});
+
+unsafe fn for_each<F: FnMut(Object) -> Result<()>>(ctx: Context, collection: Object, mut f: F) -> Result<()> {
+    let collection_binding = JavaCollection::bound(ctx)?;
+    let iterator_binding = JavaIterator::bound(ctx)?;
+
+    let iterator = call_method::<Object, Object>(ctx, collection, collection_binding.iterator, &[])?;
+    let result = (|| {
+        while call_method::<jboolean, bool>(ctx, iterator, iterator_binding.has_next, &[])? {
+            f(call_method::<Object, Object>(ctx, iterator, iterator_binding.next, &[])?)?;
+        }
+
+        Ok(())
+    })();
+
+    call!(v1_1, ctx, DeleteLocalRef, iterator);
+
+    result
+}
+
+// consumes the collection reference like the other FromJava impls.
+unsafe fn collect_from<T: FromJava<Object>>(ctx: Context, collection: Object) -> Result<Vec<T>> {
+    let collected = (|| {
+        let size = call_method::<jint, jint>(ctx, collection, JavaCollection::bound(ctx)?.size, &[])?;
+
+        let mut result = Vec::with_capacity(size.max(0) as usize);
+        for_each(ctx, collection, |element| {
+            result.push(T::from_java(element, ctx)?);
+
+            Ok(())
+        })?;
+
+        Ok(result)
+    })();
+
+    call!(v1_1, ctx, DeleteLocalRef, collection);
+
+    collected
+}
+
+unsafe fn collect_entries<K: FromJava<Object>, V: FromJava<Object>, C: Extend<(K, V)>>(
+    ctx: Context,
+    map: Object,
+    mut result: C,
+) -> Result<C> {
+    let collected = (|| {
+        let entry_binding = JavaMapEntry::bound(ctx)?;
+        let entries = call_method::<Object, Object>(ctx, map, JavaMap::bound(ctx)?.entry_set, &[])?;
+
+        let collected = for_each(ctx, entries, |entry| {
+            let pair = (|| {
+                let key = call_method::<Object, Object>(ctx, entry, entry_binding.get_key, &[])?;
+
+                match call_method::<Object, Object>(ctx, entry, entry_binding.get_value, &[]) {
+                    Ok(value) => Ok((key, value)),
+                    Err(throwable) => {
+                        call!(v1_1, ctx, DeleteLocalRef, key);
+
+                        Err(throwable)
+                    }
+                }
+            })();
+
+            call!(v1_1, ctx, DeleteLocalRef, entry);
+
+            let (key, value) = pair?;
+            let key = match K::from_java(key, ctx) {
+                Ok(key) => key,
+                Err(throwable) => {
+                    call!(v1_1, ctx, DeleteLocalRef, value);
+
+                    return Err(throwable);
+                }
+            };
+
+            result.extend([(key, V::from_java(value, ctx)?)]);
+
+            Ok(())
+        });
+
+        call!(v1_1, ctx, DeleteLocalRef, entries);
+
+        collected
+    })();
+
+    call!(v1_1, ctx, DeleteLocalRef, map);
+
+    collected.map(|_| result)
+}
+
+fn new_collection<B, R, T, I>(ctx: Context, bound: &Bound<B>, constructor: MethodId, elements: I) -> Result<R>
+where
+    R: Reference,
+    T: IntoJava<Object>,
+    I: ExactSizeIterator<Item = T>,
+{
+    with_pushed_frame(ctx, 2, || unsafe {
+        let add = JavaCollection::bound(ctx)?.add;
+        let collection = new_object::<Object, Object>(ctx, bound.class(), constructor, &[(elements.len() as jint).into_value()])?;
+
+        for element in elements {
+            with_element_frame(ctx, || {
+                let element = element.into_java(ctx)?;
+
+                call_method::<jboolean, bool>(ctx, collection, add, &[element.into_value()]).map(|_| ())
+            })?;
+        }
+
+        Ok(R::from_object(collection))
+    })
+}
+
+fn new_map<B, K, V, I>(ctx: Context, bound: &Bound<B>, constructor: MethodId, entries: I) -> Result<MapRef>
+where
+    K: IntoJava<Object>,
+    V: IntoJava<Object>,
+    I: ExactSizeIterator<Item = (K, V)>,
+{
+    with_pushed_frame(ctx, 2, || unsafe {
+        let put = JavaMap::bound(ctx)?.put;
+        let map = new_object::<Object, Object>(ctx, bound.class(), constructor, &[(entries.len() as jint).into_value()])?;
+
+        for (key, value) in entries {
+            with_element_frame(ctx, || {
+                let key = key.into_java(ctx)?;
+                let value = value.into_java(ctx)?;
+
+                call_method::<Object, Object>(ctx, map, put, &[key.into_value(), value.into_value()]).map(|_| ())
+            })?;
+        }
+
+        Ok(MapRef(map))
+    })
+}
+
+impl<T: FromJava<Object>> FromJava<ListRef> for Vec<T> {
+    unsafe fn from_java(value: ListRef, ctx: Context) -> Result<Self> {
+        collect_from(ctx, value.0)
+    }
+}
+
+// Vec<T> also converts to java arrays, callers relying on inference now have to name the target type.
+impl<T: IntoJava<Object>> IntoJava<ListRef> for Vec<T> {
+    fn into_java(self, ctx: Context) -> Result<ListRef> {
+        let bound = JavaArrayList::bound(ctx)?;
+
+        new_collection(ctx, bound, bound.new, self.into_iter())
+    }
+}
+
+impl<T: FromJava<Object> + Eq + Hash> FromJava<SetRef> for HashSet<T> {
+    unsafe fn from_java(value: SetRef, ctx: Context) -> Result<Self> {
+        Ok(collect_from::<T>(ctx, value.0)?.into_iter().collect())
+    }
+}
+
+impl<T: IntoJava<Object>> IntoJava<SetRef> for HashSet<T> {
+    fn into_java(self, ctx: Context) -> Result<SetRef> {
+        let bound = JavaHashSet::bound(ctx)?;
+
+        new_collection(ctx, bound, bound.new, self.into_iter())
+    }
+}
+
+impl<T: FromJava<Object> + Ord> FromJava<SetRef> for BTreeSet<T> {
+    unsafe fn from_java(value: SetRef, ctx: Context) -> Result<Self> {
+        Ok(collect_from::<T>(ctx, value.0)?.into_iter().collect())
+    }
+}
+
+impl<T: IntoJava<Object>> IntoJava<SetRef> for BTreeSet<T> {
+    fn into_java(self, ctx: Context) -> Result<SetRef> {
+        let bound = JavaLinkedHashSet::bound(ctx)?;
+
+        new_collection(ctx, bound, bound.new, self.into_iter())
+    }
+}
+
+impl<K: FromJava<Object> + Eq + Hash, V: FromJava<Object>> FromJava<MapRef> for HashMap<K, V> {
+    unsafe fn from_java(value: MapRef, ctx: Context) -> Result<Self> {
+        collect_entries(ctx, value.0, HashMap::new())
+    }
+}
+
+impl<K: IntoJava<Object>, V: IntoJava<Object>> IntoJava<MapRef> for HashMap<K, V> {
+    fn into_java(self, ctx: Context) -> Result<MapRef> {
+        let bound = JavaHashMap::bound(ctx)?;
+
+        new_map(ctx, bound, bound.new, self.into_iter())
+    }
+}
+
+impl<K: FromJava<Object> + Ord, V: FromJava<Object>> FromJava<MapRef> for BTreeMap<K, V> {
+    unsafe fn from_java(value: MapRef, ctx: Context) -> Result<Self> {
+        collect_entries(ctx, value.0, BTreeMap::new())
+    }
+}
+
+impl<K: IntoJava<Object>, V: IntoJava<Object>> IntoJava<MapRef> for BTreeMap<K, V> {
+    fn into_java(self, ctx: Context) -> Result<MapRef> {
+        let bound = JavaLinkedHashMap::bound(ctx)?;
+
+        new_map(ctx, bound, bound.new, self.into_iter())
+    }
+}
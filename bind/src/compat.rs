@@ -0,0 +1,92 @@
+use jni::{
+    objects::{GlobalRef, JByteBuffer, JClass, JObject, JObjectArray, JPrimitiveArray, JString, JThrowable, TypeArray},
+    JNIEnv,
+};
+
+use crate::{Context, FromJava, IntoJava, JavaVm, Object, Reference, Result};
+
+// jni depends on an older jni-sys, the raw pointers are the same but the types are not.
+pub fn context_from_jni(env: &JNIEnv) -> Context {
+    env.get_raw().cast()
+}
+
+/// # Safety
+///
+/// `ctx` must be the env of the current thread and the returned env must not outlive it.
+pub unsafe fn context_into_jni<'local>(ctx: Context) -> JNIEnv<'local> {
+    JNIEnv::from_raw(ctx.cast()).expect("jni env must not be null")
+}
+
+pub fn object_from_jni(obj: &JObject) -> Object {
+    obj.as_raw().cast()
+}
+
+/// # Safety
+///
+/// `obj` must be null or a reference that stays live for `'local`.
+pub unsafe fn object_into_jni<'local>(obj: Object) -> JObject<'local> {
+    JObject::from_raw(obj.cast())
+}
+
+impl JavaVm {
+    pub fn from_jni(vm: &jni::JavaVM) -> Self {
+        unsafe { JavaVm::from_raw(vm.get_java_vm_pointer().cast()) }
+    }
+
+    pub fn to_jni(&self) -> jni::JavaVM {
+        unsafe { jni::JavaVM::from_raw(self.as_raw().cast()).expect("java vm must not be null") }
+    }
+}
+
+macro_rules! reference_impl {
+    ($typ:ident) => {
+        impl<'local, R: Reference> FromJava<R> for $typ<'local> {
+            unsafe fn from_java(value: R, _: Context) -> Result<Self> {
+                Ok($typ::from_raw(value.as_object().cast()))
+            }
+        }
+
+        impl<'local, R: Reference> IntoJava<R> for $typ<'local> {
+            fn into_java(self, _: Context) -> Result<R> {
+                Ok(unsafe { R::from_object(self.into_raw().cast()) })
+            }
+        }
+
+        impl<'local, R: Reference> IntoJava<R> for &$typ<'local> {
+            fn into_java(self, _: Context) -> Result<R> {
+                Ok(unsafe { R::from_object(self.as_raw().cast()) })
+            }
+        }
+    };
+}
+
+reference_impl!(JObject);
+reference_impl!(JClass);
+reference_impl!(JString);
+reference_impl!(JThrowable);
+reference_impl!(JObjectArray);
+reference_impl!(JByteBuffer);
+
+impl<'local, T: TypeArray, R: Reference> FromJava<R> for JPrimitiveArray<'local, T> {
+    unsafe fn from_java(value: R, _: Context) -> Result<Self> {
+        Ok(JPrimitiveArray::from_raw(value.as_object().cast()))
+    }
+}
+
+impl<'local, T: TypeArray, R: Reference> IntoJava<R> for JPrimitiveArray<'local, T> {
+    fn into_java(self, _: Context) -> Result<R> {
+        Ok(unsafe { R::from_object(self.into_raw().cast()) })
+    }
+}
+
+impl<'local, T: TypeArray, R: Reference> IntoJava<R> for &JPrimitiveArray<'local, T> {
+    fn into_java(self, _: Context) -> Result<R> {
+        Ok(unsafe { R::from_object(self.as_raw().cast()) })
+    }
+}
+
+impl<R: Reference> IntoJava<R> for &GlobalRef {
+    fn into_java(self, _: Context) -> Result<R> {
+        Ok(unsafe { R::from_object(self.as_obj().as_raw().cast()) })
+    }
+}
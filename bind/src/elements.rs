@@ -0,0 +1,346 @@
+use std::{
+    ffi::c_void,
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+};
+
+use jni_sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jshort, jsize, JNI_ABORT, JNI_COMMIT};
+use paste::paste;
+
+use crate::{
+    call, find_class, invoke_with_throwable, is_instance_of, new_throwable, Context, FromJava, Object, Reference, Result,
+};
+
+/// # Safety
+///
+/// Implementors must be the jni element type of the array functions they call and `SIGNATURE` must be the
+/// signature of the matching java array, e.g. `jint` and `[I`.
+pub unsafe trait PrimitiveElement: Copy {
+    const SIGNATURE: &'static str;
+
+    /// # Safety
+    ///
+    /// `array` must be a live `SIGNATURE` array and the returned elements must be released exactly once.
+    unsafe fn get_elements(ctx: Context, array: Object, is_copy: *mut jboolean) -> *mut Self;
+    /// # Safety
+    ///
+    /// `elements` must have been returned by `get_elements` for `array`, `JNI_COMMIT` keeps them alive.
+    unsafe fn release_elements(ctx: Context, array: Object, elements: *mut Self, mode: jint);
+    /// # Safety
+    ///
+    /// `array` must be a live `SIGNATURE` array and `buf` must be valid for `length` writes.
+    unsafe fn get_region(ctx: Context, array: Object, start: jsize, length: jsize, buf: *mut Self);
+    /// # Safety
+    ///
+    /// `array` must be a live `SIGNATURE` array and `buf` must be valid for `length` reads.
+    unsafe fn set_region(ctx: Context, array: Object, start: jsize, length: jsize, buf: *const Self);
+}
+
+macro_rules! primitive_element_impl {
+    ($typ:ty, $java_type:ident, $signature:literal) => {
+        paste! {
+            unsafe impl PrimitiveElement for $typ {
+                const SIGNATURE: &'static str = $signature;
+
+                unsafe fn get_elements(ctx: Context, array: Object, is_copy: *mut jboolean) -> *mut Self {
+                    call!(v1_1, ctx, [<Get $java_type ArrayElements>], array, is_copy)
+                }
+
+                unsafe fn release_elements(ctx: Context, array: Object, elements: *mut Self, mode: jint) {
+                    call!(v1_1, ctx, [<Release $java_type ArrayElements>], array, elements, mode)
+                }
+
+                unsafe fn get_region(ctx: Context, array: Object, start: jsize, length: jsize, buf: *mut Self) {
+                    call!(v1_1, ctx, [<Get $java_type ArrayRegion>], array, start, length, buf)
+                }
+
+                unsafe fn set_region(ctx: Context, array: Object, start: jsize, length: jsize, buf: *const Self) {
+                    call!(v1_1, ctx, [<Set $java_type ArrayRegion>], array, start, length, buf)
+                }
+            }
+        }
+    };
+}
+
+primitive_element_impl!(jboolean, Boolean, "[Z");
+primitive_element_impl!(jbyte, Byte, "[B");
+primitive_element_impl!(jchar, Char, "[C");
+primitive_element_impl!(jshort, Short, "[S");
+primitive_element_impl!(jint, Int, "[I");
+primitive_element_impl!(jlong, Long, "[J");
+primitive_element_impl!(jfloat, Float, "[F");
+primitive_element_impl!(jdouble, Double, "[D");
+
+#[repr(transparent)]
+pub struct PrimitiveArrayRef<T> {
+    object: Object,
+    _element: PhantomData<fn() -> T>,
+}
+
+impl<T> Clone for PrimitiveArrayRef<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PrimitiveArrayRef<T> {}
+
+unsafe impl<T> Reference for PrimitiveArrayRef<T> {
+    fn as_object(self) -> Object {
+        self.object
+    }
+
+    unsafe fn from_object(object: Object) -> Self {
+        PrimitiveArrayRef {
+            object,
+            _element: PhantomData,
+        }
+    }
+}
+
+impl<T> FromJava<PrimitiveArrayRef<T>> for Object {
+    unsafe fn from_java(value: PrimitiveArrayRef<T>, _: Context) -> Result<Self> {
+        Ok(value.object)
+    }
+}
+
+impl<T> From<PrimitiveArrayRef<T>> for Object {
+    fn from(value: PrimitiveArrayRef<T>) -> Self {
+        value.object
+    }
+}
+
+impl<T: PrimitiveElement> PrimitiveArrayRef<T> {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `object` a live local or global reference.
+    pub unsafe fn downcast(ctx: Context, object: Object) -> Result<Option<Self>> {
+        let class = find_class(ctx, T::SIGNATURE)?;
+        let is_instance = is_instance_of(ctx, object, class);
+        call!(v1_1, ctx, DeleteLocalRef, class);
+
+        Ok(is_instance.then(|| PrimitiveArrayRef::from_object(object)))
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ReleaseMode {
+    CopyBack,
+    Abort,
+}
+
+impl ReleaseMode {
+    fn as_raw(self) -> jint {
+        match self {
+            ReleaseMode::CopyBack => 0,
+            ReleaseMode::Abort => JNI_ABORT,
+        }
+    }
+}
+
+unsafe fn array_length(ctx: Context, array: Object) -> Result<usize> {
+    if array.is_null() {
+        return Err(new_throwable(ctx, "java/lang/NullPointerException", "array is null"));
+    }
+
+    Ok(call!(v1_1, ctx, GetArrayLength, array) as usize)
+}
+
+pub struct ArrayElements<'a, T: PrimitiveElement> {
+    ctx: Context,
+    array: Object,
+    elements: *mut T,
+    length: usize,
+    is_copy: bool,
+    mode: ReleaseMode,
+    _array: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T: PrimitiveElement> ArrayElements<'a, T> {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `array` a live reference. No other `ArrayElements` or
+    /// `CriticalArray` may exist for the same java array, copies of the handle included, as each hands out
+    /// `&mut [T]` over the same memory.
+    pub unsafe fn new(ctx: Context, array: &'a mut PrimitiveArrayRef<T>, mode: ReleaseMode) -> Result<Self> {
+        let array = array.object;
+        let length = array_length(ctx, array)?;
+
+        let mut is_copy: jboolean = false;
+        let elements = invoke_with_throwable(ctx, || T::get_elements(ctx, array, &mut is_copy))?;
+        if elements.is_null() {
+            return Err(new_throwable(
+                ctx,
+                "java/lang/OutOfMemoryError",
+                "unable to pin array elements",
+            ));
+        }
+
+        Ok(ArrayElements {
+            ctx,
+            array,
+            elements,
+            length,
+            is_copy,
+            mode,
+            _array: PhantomData,
+        })
+    }
+
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    pub fn release_mode(&self) -> ReleaseMode {
+        self.mode
+    }
+
+    pub fn set_release_mode(&mut self, mode: ReleaseMode) {
+        self.mode = mode;
+    }
+
+    // writes the copied elements back while keeping them borrowed, a no-op for pinned arrays.
+    pub fn commit(&mut self) {
+        if self.is_copy {
+            unsafe { T::release_elements(self.ctx, self.array, self.elements, JNI_COMMIT) };
+        }
+    }
+
+    pub fn abort(mut self) {
+        self.mode = ReleaseMode::Abort;
+    }
+}
+
+impl<T: PrimitiveElement> Deref for ArrayElements<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.elements, self.length) }
+    }
+}
+
+impl<T: PrimitiveElement> DerefMut for ArrayElements<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.elements, self.length) }
+    }
+}
+
+impl<T: PrimitiveElement> Drop for ArrayElements<'_, T> {
+    fn drop(&mut self) {
+        unsafe { T::release_elements(self.ctx, self.array, self.elements, self.mode.as_raw()) };
+    }
+}
+
+pub struct CriticalArray<'a, T: PrimitiveElement> {
+    ctx: Context,
+    array: Object,
+    elements: *mut T,
+    length: usize,
+    is_copy: bool,
+    mode: ReleaseMode,
+    _array: PhantomData<&'a mut [T]>,
+}
+
+impl<'a, T: PrimitiveElement> CriticalArray<'a, T> {
+    /// # Safety
+    ///
+    /// Same as `ArrayElements::new`. Until the returned guard is dropped the thread is in a critical region:
+    /// it must not call any other jni function nor block waiting on another java thread.
+    pub unsafe fn new(ctx: Context, array: &'a mut PrimitiveArrayRef<T>, mode: ReleaseMode) -> Result<Self> {
+        let array = array.object;
+        let length = array_length(ctx, array)?;
+
+        let mut is_copy: jboolean = false;
+        let elements = call!(v1_2, ctx, GetPrimitiveArrayCritical, array, &mut is_copy) as *mut T;
+        if elements.is_null() {
+            return Err(new_throwable(
+                ctx,
+                "java/lang/OutOfMemoryError",
+                "unable to pin array elements",
+            ));
+        }
+
+        Ok(CriticalArray {
+            ctx,
+            array,
+            elements,
+            length,
+            is_copy,
+            mode,
+            _array: PhantomData,
+        })
+    }
+
+    pub fn is_copy(&self) -> bool {
+        self.is_copy
+    }
+
+    pub fn set_release_mode(&mut self, mode: ReleaseMode) {
+        self.mode = mode;
+    }
+
+    pub fn abort(mut self) {
+        self.mode = ReleaseMode::Abort;
+    }
+}
+
+impl<T: PrimitiveElement> Deref for CriticalArray<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { std::slice::from_raw_parts(self.elements, self.length) }
+    }
+}
+
+impl<T: PrimitiveElement> DerefMut for CriticalArray<'_, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { std::slice::from_raw_parts_mut(self.elements, self.length) }
+    }
+}
+
+impl<T: PrimitiveElement> Drop for CriticalArray<'_, T> {
+    fn drop(&mut self) {
+        unsafe {
+            call!(
+                v1_2,
+                self.ctx,
+                ReleasePrimitiveArrayCritical,
+                self.array,
+                self.elements as *mut c_void,
+                self.mode.as_raw()
+            )
+        };
+    }
+}
+
+/// # Safety
+///
+/// `ctx` must be the env of the current thread, outside of a critical region, and `array` a live reference.
+pub unsafe fn get_array_region<T: PrimitiveElement>(
+    ctx: Context,
+    array: PrimitiveArrayRef<T>,
+    start: usize,
+    buf: &mut [T],
+) -> Result<()> {
+    array_length(ctx, array.object)?;
+
+    invoke_with_throwable(ctx, || {
+        T::get_region(ctx, array.object, start as jsize, buf.len() as jsize, buf.as_mut_ptr())
+    })
+}
+
+/// # Safety
+///
+/// `ctx` must be the env of the current thread, outside of a critical region, and `array` a live reference.
+pub unsafe fn set_array_region<T: PrimitiveElement>(
+    ctx: Context,
+    array: PrimitiveArrayRef<T>,
+    start: usize,
+    buf: &[T],
+) -> Result<()> {
+    array_length(ctx, array.object)?;
+
+    invoke_with_throwable(ctx, || {
+        T::set_region(ctx, array.object, start as jsize, buf.len() as jsize, buf.as_ptr())
+    })
+}
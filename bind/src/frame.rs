@@ -1,16 +1,16 @@
 use std::ptr::null_mut;
 
-use jni_sys::{jboolean, jbyte, jchar, jdouble, jfieldID, jfloat, jint, jlong, jmethodID, jobject, jshort, jsize};
+use jni_sys::{jboolean, jbyte, jchar, jdouble, jfloat, jint, jlong, jobject, jshort, jsize};
 
-use crate::{call, Context, Result};
+use crate::{call, Context, FieldId, MethodId, Reference, Result};
 
 pub trait AsMutObject {
     fn as_mut_object(&mut self) -> Option<&mut jobject>;
 }
 
-impl AsMutObject for jobject {
+impl<R: Reference> AsMutObject for R {
     fn as_mut_object(&mut self) -> Option<&mut jobject> {
-        Some(self)
+        Some(unsafe { &mut *(self as *mut R).cast::<jobject>() })
     }
 }
 
@@ -41,10 +41,11 @@ none_mut_object!(jint);
 none_mut_object!(jlong);
 none_mut_object!(jfloat);
 none_mut_object!(jdouble);
-none_mut_object!(jmethodID);
-none_mut_object!(jfieldID);
+none_mut_object!(MethodId);
+none_mut_object!(FieldId);
 none_mut_object!(());
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn with_pushed_frame<R: AsMutObject, F: FnOnce() -> R>(ctx: Context, min_size: usize, f: F) -> R {
     unsafe { call!(v1_2, ctx, PushLocalFrame, min_size as jsize) };
 
@@ -58,3 +59,8 @@ pub fn with_pushed_frame<R: AsMutObject, F: FnOnce() -> R>(ctx: Context, min_siz
 
     r
 }
+
+// every element gets its own frame, large collections and arrays would exhaust the local references otherwise.
+pub(crate) fn with_element_frame<R: AsMutObject, F: FnOnce() -> R>(ctx: Context, f: F) -> R {
+    with_pushed_frame(ctx, 4, f)
+}
@@ -2,12 +2,16 @@ use std::ptr::null_mut;
 
 use jni_sys::{
     jboolean, jbooleanArray, jbyte, jbyteArray, jchar, jcharArray, jdouble, jdoubleArray, jfloat, jfloatArray, jint, jintArray,
-    jlong, jlongArray, jobject, jobjectArray, jshort, jshortArray, jsize, jstring, JNI_ABORT,
+    jlong, jlongArray, jobjectArray, jshort, jshortArray, jsize, jstring, JNI_ABORT,
 };
 
-use crate::{call, Context, Result};
+use crate::{call, new_throwable, Context, Reference, Result};
 
 pub trait FromJava<T>: Sized {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `value` a valid `T`. A local reference passed in is handed
+    /// over and may be deleted.
     unsafe fn from_java(value: T, ctx: Context) -> Result<Self>;
 }
 
@@ -29,9 +33,28 @@ primitive_impl!(jint);
 primitive_impl!(jlong);
 primitive_impl!(jfloat);
 primitive_impl!(jdouble);
-primitive_impl!(jobject);
 primitive_impl!(());
 
+macro_rules! reinterpret_impl {
+    ($typ:ty, $jni_type:ty) => {
+        impl FromJava<$jni_type> for $typ {
+            unsafe fn from_java(value: $jni_type, _: Context) -> Result<Self> {
+                Ok(value as $typ)
+            }
+        }
+    };
+}
+
+reinterpret_impl!(u8, jbyte);
+reinterpret_impl!(u16, jshort);
+
+impl FromJava<jchar> for char {
+    unsafe fn from_java(value: jchar, ctx: Context) -> Result<Self> {
+        char::from_u32(value as u32)
+            .ok_or_else(|| new_throwable(ctx, "java/lang/IllegalArgumentException", "unpaired surrogate character"))
+    }
+}
+
 impl FromJava<jstring> for String {
     unsafe fn from_java(value: jstring, ctx: Context) -> Result<Self> {
         let length = unsafe { call!(v1_1, ctx, GetStringLength, value) };
@@ -47,9 +70,15 @@ impl FromJava<jstring> for String {
     }
 }
 
-impl<T: FromJava<jobject>> FromJava<jobject> for Option<T> {
-    unsafe fn from_java(value: jobject, ctx: Context) -> Result<Self> {
-        let value = if !value.is_null() {
+impl<R: Reference> FromJava<R> for R {
+    unsafe fn from_java(value: R, _: Context) -> Result<Self> {
+        Ok(value)
+    }
+}
+
+impl<R: Reference, T: FromJava<R>> FromJava<R> for Option<T> {
+    unsafe fn from_java(value: R, ctx: Context) -> Result<Self> {
+        let value = if !value.as_object().is_null() {
             Some(T::from_java(value, ctx)?)
         } else {
             None
@@ -86,6 +115,14 @@ array_impl!(jlong, jlongArray, GetLongArrayElements, ReleaseLongArrayElements);
 array_impl!(jfloat, jfloatArray, GetFloatArrayElements, ReleaseFloatArrayElements);
 array_impl!(jdouble, jdoubleArray, GetDoubleArrayElements, ReleaseDoubleArrayElements);
 
+impl FromJava<jbyteArray> for Vec<u8> {
+    unsafe fn from_java(value: jbyteArray, ctx: Context) -> Result<Self> {
+        let bytes = Vec::<jbyte>::from_java(value, ctx)?;
+
+        Ok(bytes.into_iter().map(|b| b as u8).collect())
+    }
+}
+
 impl FromJava<jobjectArray> for Vec<String> {
     unsafe fn from_java(value: jobjectArray, ctx: Context) -> Result<Self> {
         let length = unsafe { call!(v1_1, ctx, GetArrayLength, value) };
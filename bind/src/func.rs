@@ -1,31 +1,76 @@
+// functions with parameters must take the jni env first, panics are rethrown through it as the `throws` class
+// (RuntimeException by default) and bodies returning Result<T> throw their Err.
 #[macro_export]
 macro_rules! system_fn {
     (|| $body:block) => {
         {
-            extern "system" fn __func() { $body }
+            extern "system" fn __func() {
+                if ::std::panic::catch_unwind(|| $body).is_err() {
+                    ::std::process::abort();
+                }
+            }
 
             __func
         }
     };
     (|| -> $ret:ty $body:block) => {
         {
-            extern "system" fn __func() -> $ret $body
+            extern "system" fn __func() -> $ret {
+                match ::std::panic::catch_unwind(|| $body) {
+                    Ok(value) => value,
+                    Err(_) => ::std::process::abort(),
+                }
+            }
 
             __func
         }
     };
-    (|$($name:tt:$typ:ty),*| $body:block) => {
+    (throws $exception:literal, |$env:tt: $env_typ:ty $(, $name:tt: $typ:ty)*| -> Result<$ret:ty> $body:block) => {
         {
-            extern "system" fn __func($($name:$typ),*) $body
+            extern "system" fn __func(__env: $env_typ $(, $name: $typ)*) -> $ret {
+                let __body = move || -> $crate::Result<$ret> {
+                    let $env: $env_typ = __env;
+
+                    $body
+                };
+
+                unsafe { $crate::native_call(__env, $exception, __body) }
+            }
+
+            __func
+        }
+    };
+    (throws $exception:literal, |$env:tt: $env_typ:ty $(, $name:tt: $typ:ty)*| -> $ret:ty $body:block) => {
+        {
+            extern "system" fn __func(__env: $env_typ $(, $name: $typ)*) -> $ret {
+                let __body = move || -> $ret {
+                    let $env: $env_typ = __env;
+
+                    $body
+                };
+
+                unsafe { $crate::catch_panic(__env, $exception, __body) }
+            }
 
             __func
         }
     };
-    (|$($name:tt:$typ:ty),*| -> $ret:ty $body:block) => {
+    (throws $exception:literal, |$env:tt: $env_typ:ty $(, $name:tt: $typ:ty)*| $body:block) => {
         {
-            extern "system" fn __func($($name:$typ),*) -> $ret $body
+            extern "system" fn __func(__env: $env_typ $(, $name: $typ)*) {
+                let __body = move || {
+                    let $env: $env_typ = __env;
+
+                    $body
+                };
+
+                unsafe { $crate::catch_panic(__env, $exception, __body) }
+            }
 
             __func
         }
     };
+    (|$env:tt: $env_typ:ty $(, $name:tt: $typ:ty)*| $($rest:tt)*) => {
+        $crate::system_fn!(throws "java/lang/RuntimeException", |$env: $env_typ $(, $name: $typ)*| $($rest)*)
+    };
 }
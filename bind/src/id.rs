@@ -0,0 +1,71 @@
+use std::sync::OnceLock;
+
+use jni_sys::{jfieldID, jmethodID};
+
+use crate::Result;
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct MethodId(jmethodID);
+
+#[repr(transparent)]
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct FieldId(jfieldID);
+
+// method and field ids stay valid on every thread as long as the declaring class is not unloaded.
+unsafe impl Send for MethodId {}
+unsafe impl Sync for MethodId {}
+unsafe impl Send for FieldId {}
+unsafe impl Sync for FieldId {}
+
+impl MethodId {
+    /// # Safety
+    ///
+    /// `raw` must be a method id returned by the vm, used only with its declaring class and its subclasses.
+    pub unsafe fn from_raw(raw: jmethodID) -> Self {
+        MethodId(raw)
+    }
+
+    pub fn as_raw(self) -> jmethodID {
+        self.0
+    }
+}
+
+impl FieldId {
+    /// # Safety
+    ///
+    /// `raw` must be a field id returned by the vm, used only with its declaring class and its subclasses.
+    pub unsafe fn from_raw(raw: jfieldID) -> Self {
+        FieldId(raw)
+    }
+
+    pub fn as_raw(self) -> jfieldID {
+        self.0
+    }
+}
+
+pub struct LazyId<T> {
+    id: OnceLock<T>,
+}
+
+impl<T: Copy> LazyId<T> {
+    pub const fn new() -> Self {
+        LazyId { id: OnceLock::new() }
+    }
+
+    pub fn get_or_find<F: FnOnce() -> Result<T>>(&self, find: F) -> Result<T> {
+        if let Some(id) = self.id.get() {
+            return Ok(*id);
+        }
+
+        let id = find()?;
+
+        Ok(*self.id.get_or_init(|| id))
+    }
+}
+
+impl<T: Copy> Default for LazyId<T> {
+    fn default() -> Self {
+        LazyId::new()
+    }
+}
@@ -2,10 +2,10 @@ use std::ptr::null_mut;
 
 use jni_sys::{
     jboolean, jbooleanArray, jbyte, jbyteArray, jchar, jcharArray, jdouble, jdoubleArray, jfloat, jfloatArray, jint, jintArray,
-    jlong, jlongArray, jobject, jobjectArray, jshort, jshortArray, jsize, jstring, jvalue,
+    jlong, jlongArray, jobjectArray, jshort, jshortArray, jsize, jstring, jvalue,
 };
 
-use crate::{call, with_pushed_frame, Context, Result};
+use crate::{call, new_throwable, with_pushed_frame, Context, Reference, Result};
 
 pub trait IntoJava<T> {
     fn into_java(self, ctx: Context) -> Result<T>;
@@ -29,10 +29,36 @@ primitive_impl!(jint);
 primitive_impl!(jlong);
 primitive_impl!(jfloat);
 primitive_impl!(jdouble);
-primitive_impl!(jobject);
 primitive_impl!(());
 
+macro_rules! reinterpret_impl {
+    ($typ:ty, $jni_type:ty) => {
+        impl IntoJava<$jni_type> for $typ {
+            fn into_java(self, _: Context) -> Result<$jni_type> {
+                Ok(self as $jni_type)
+            }
+        }
+    };
+}
+
+reinterpret_impl!(u8, jbyte);
+reinterpret_impl!(u16, jshort);
+
+// characters out of the basic multilingual plane can not be passed as a single jchar.
+impl IntoJava<jchar> for char {
+    fn into_java(self, ctx: Context) -> Result<jchar> {
+        jchar::try_from(self as u32).map_err(|_| {
+            new_throwable(
+                ctx,
+                "java/lang/IllegalArgumentException",
+                "character out of the basic multilingual plane",
+            )
+        })
+    }
+}
+
 impl IntoJava<jstring> for &str {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     fn into_java(self, ctx: Context) -> Result<jstring> {
         let utf16_chars = self.encode_utf16().collect::<Vec<_>>();
 
@@ -52,11 +78,17 @@ impl IntoJava<jstring> for &String {
     }
 }
 
-impl<T: IntoJava<jobject>> IntoJava<jobject> for Option<T> {
-    fn into_java(self, ctx: Context) -> Result<jobject> {
+impl<R: Reference> IntoJava<R> for R {
+    fn into_java(self, _: Context) -> Result<R> {
+        Ok(self)
+    }
+}
+
+impl<R: Reference, T: IntoJava<R>> IntoJava<R> for Option<T> {
+    fn into_java(self, ctx: Context) -> Result<R> {
         let value = match self {
             Some(v) => v.into_java(ctx)?,
-            None => null_mut(),
+            None => unsafe { R::from_object(null_mut()) },
         };
 
         Ok(value)
@@ -66,6 +98,7 @@ impl<T: IntoJava<jobject>> IntoJava<jobject> for Option<T> {
 macro_rules! array_impl {
     ($element_type:tt, $array_type:tt, $new_func:ident, $set_func:ident) => {
         impl IntoJava<$array_type> for &[$element_type] {
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             fn into_java(self, ctx: Context) -> Result<$array_type> {
                 let array = unsafe { call!(v1_1, ctx, $new_func, self.len() as jsize) };
 
@@ -76,6 +109,7 @@ macro_rules! array_impl {
         }
 
         impl IntoJava<$array_type> for Vec<$element_type> {
+            #[allow(clippy::not_unsafe_ptr_arg_deref)]
             fn into_java(self, ctx: Context) -> Result<$array_type> {
                 let array = unsafe { call!(v1_1, ctx, $new_func, self.len() as jsize) };
 
@@ -96,7 +130,20 @@ array_impl!(jlong, jlongArray, NewLongArray, SetLongArrayRegion);
 array_impl!(jfloat, jfloatArray, NewFloatArray, SetFloatArrayRegion);
 array_impl!(jdouble, jdoubleArray, NewDoubleArray, SetDoubleArrayRegion);
 
+impl IntoJava<jbyteArray> for &[u8] {
+    fn into_java(self, ctx: Context) -> Result<jbyteArray> {
+        IntoJava::<jbyteArray>::into_java(unsafe { &*(self as *const [u8] as *const [jbyte]) }, ctx)
+    }
+}
+
+impl IntoJava<jbyteArray> for Vec<u8> {
+    fn into_java(self, ctx: Context) -> Result<jbyteArray> {
+        IntoJava::<jbyteArray>::into_java(&self[..], ctx)
+    }
+}
+
 impl IntoJava<jobjectArray> for &[&str] {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
     fn into_java(self, ctx: Context) -> Result<jobjectArray> {
         with_pushed_frame(ctx, self.len(), || {
             let array = unsafe {
@@ -105,7 +152,7 @@ impl IntoJava<jobjectArray> for &[&str] {
                     ctx,
                     NewObjectArray,
                     self.len() as jsize,
-                    call!(v1_1, ctx, FindClass, "java/lang/String\0".as_ptr().cast()),
+                    call!(v1_1, ctx, FindClass, c"java/lang/String".as_ptr()),
                     null_mut()
                 )
             };
@@ -162,4 +209,15 @@ value_impl!(jint, i);
 value_impl!(jlong, j);
 value_impl!(jfloat, f);
 value_impl!(jdouble, d);
-value_impl!(jobject, l);
+
+impl IntoValue for u8 {
+    fn into_value(self) -> jvalue {
+        jvalue { b: self as jbyte }
+    }
+}
+
+impl<R: Reference> IntoValue for R {
+    fn into_value(self) -> jvalue {
+        jvalue { l: self.as_object() }
+    }
+}
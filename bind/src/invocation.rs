@@ -0,0 +1,310 @@
+use std::{
+    env,
+    error::Error,
+    ffi::{c_char, c_void, CString},
+    fmt::{Display, Formatter},
+    path::{Path, PathBuf},
+    ptr::null_mut,
+    sync::OnceLock,
+};
+
+use jni_sys::{jint, jsize, JavaVM, JavaVMInitArgs, JavaVMOption, JNI_OK, JNI_VERSION_1_8};
+use libloading::Library;
+
+use crate::{set_java_vm, JavaVm, VmError};
+
+type CreateJavaVm = unsafe extern "system" fn(*mut *mut JavaVM, *mut *mut c_void, *mut c_void) -> jint;
+type GetCreatedJavaVms = unsafe extern "system" fn(*mut *mut JavaVM, jsize, *mut jsize) -> jint;
+
+pub type VfprintfHook = unsafe extern "system" fn(stream: *mut c_void, format: *const c_char, args: *mut c_void) -> jint;
+pub type ExitHook = unsafe extern "system" fn(code: jint);
+
+#[derive(Debug)]
+pub enum InvocationError {
+    NotFound,
+    Load(String),
+    AlreadyLoaded(PathBuf),
+    Vm(VmError),
+}
+
+impl Display for InvocationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvocationError::NotFound => f.write_str("unable to locate libjvm, set JAVA_HOME or put java on PATH"),
+            InvocationError::Load(message) => write!(f, "unable to load libjvm: {}", message),
+            InvocationError::AlreadyLoaded(path) => write!(f, "libjvm already loaded from {}", path.display()),
+            InvocationError::Vm(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl Error for InvocationError {}
+
+impl From<VmError> for InvocationError {
+    fn from(value: VmError) -> Self {
+        InvocationError::Vm(value)
+    }
+}
+
+// jdk 8 keeps libjvm under an architecture specific directory.
+fn jdk8_arch() -> &'static str {
+    match env::consts::ARCH {
+        "x86_64" => "amd64",
+        "x86" => "i386",
+        "powerpc64" if cfg!(target_endian = "little") => "ppc64le",
+        "powerpc64" => "ppc64",
+        "sparc64" => "sparcv9",
+        arch => arch,
+    }
+}
+
+fn library_candidates() -> Vec<PathBuf> {
+    vec![
+        PathBuf::from("lib/server/libjvm.so"),
+        PathBuf::from(format!("jre/lib/{}/server/libjvm.so", jdk8_arch())),
+        PathBuf::from("lib/server/libjvm.dylib"),
+        PathBuf::from("jre/lib/server/libjvm.dylib"),
+        PathBuf::from("bin/server/jvm.dll"),
+        PathBuf::from("jre/bin/server/jvm.dll"),
+    ]
+}
+
+fn find_in_java_home(java_home: &Path) -> Option<PathBuf> {
+    library_candidates()
+        .into_iter()
+        .map(|c| java_home.join(c))
+        .find(|p| p.is_file())
+}
+
+pub fn discover_jvm_library() -> Option<PathBuf> {
+    if let Some(java_home) = env::var_os("JAVA_HOME") {
+        if let Some(path) = find_in_java_home(Path::new(&java_home)) {
+            return Some(path);
+        }
+    }
+
+    let java = env::split_paths(&env::var_os("PATH")?)
+        .map(|p| p.join(if cfg!(windows) { "java.exe" } else { "java" }))
+        .find(|p| p.is_file())?;
+
+    // <java_home>/bin/java
+    let java_home = java.canonicalize().ok()?.parent()?.parent()?.to_path_buf();
+
+    find_in_java_home(&java_home)
+}
+
+struct JvmLibrary {
+    path: PathBuf,
+    create_java_vm: CreateJavaVm,
+    get_created_java_vms: GetCreatedJavaVms,
+    _library: Library,
+}
+
+static LIBRARY: OnceLock<JvmLibrary> = OnceLock::new();
+
+impl JvmLibrary {
+    unsafe fn load(path: &Path) -> Result<Self, InvocationError> {
+        let library = Library::new(path).map_err(|e| InvocationError::Load(e.to_string()))?;
+        let create_java_vm = *library
+            .get::<CreateJavaVm>(b"JNI_CreateJavaVM\0")
+            .map_err(|e| InvocationError::Load(e.to_string()))?;
+        let get_created_java_vms = *library
+            .get::<GetCreatedJavaVms>(b"JNI_GetCreatedJavaVMs\0")
+            .map_err(|e| InvocationError::Load(e.to_string()))?;
+
+        Ok(JvmLibrary {
+            path: path.to_path_buf(),
+            create_java_vm,
+            get_created_java_vms,
+            _library: library,
+        })
+    }
+
+    // libjvm can not be unloaded safely, so the first loaded library is kept for the whole process.
+    fn get_or_load(path: Option<&Path>) -> Result<&'static JvmLibrary, InvocationError> {
+        if let Some(library) = LIBRARY.get() {
+            return match path {
+                Some(path) if path != library.path => Err(InvocationError::AlreadyLoaded(library.path.clone())),
+                _ => Ok(library),
+            };
+        }
+
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => discover_jvm_library().ok_or(InvocationError::NotFound)?,
+        };
+        let library = unsafe { JvmLibrary::load(&path)? };
+
+        Ok(LIBRARY.get_or_init(|| library))
+    }
+}
+
+pub struct JavaVmBuilder {
+    library_path: Option<PathBuf>,
+    version: jint,
+    class_path: Vec<PathBuf>,
+    properties: Vec<(String, String)>,
+    options: Vec<String>,
+    check_jni: bool,
+    ignore_unrecognized: bool,
+    vfprintf: Option<VfprintfHook>,
+    exit: Option<ExitHook>,
+}
+
+impl Default for JavaVmBuilder {
+    fn default() -> Self {
+        JavaVmBuilder::new()
+    }
+}
+
+impl JavaVmBuilder {
+    pub fn new() -> Self {
+        JavaVmBuilder {
+            library_path: None,
+            version: JNI_VERSION_1_8,
+            class_path: Vec::new(),
+            properties: Vec::new(),
+            options: Vec::new(),
+            check_jni: false,
+            ignore_unrecognized: false,
+            vfprintf: None,
+            exit: None,
+        }
+    }
+
+    pub fn library_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.library_path = Some(path.into());
+        self
+    }
+
+    pub fn version(mut self, version: jint) -> Self {
+        self.version = version;
+        self
+    }
+
+    pub fn class_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.class_path.push(path.into());
+        self
+    }
+
+    pub fn property(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.properties.push((key.into(), value.into()));
+        self
+    }
+
+    pub fn option(mut self, option: impl Into<String>) -> Self {
+        self.options.push(option.into());
+        self
+    }
+
+    pub fn check_jni(mut self, check_jni: bool) -> Self {
+        self.check_jni = check_jni;
+        self
+    }
+
+    pub fn ignore_unrecognized(mut self, ignore_unrecognized: bool) -> Self {
+        self.ignore_unrecognized = ignore_unrecognized;
+        self
+    }
+
+    pub fn vfprintf(mut self, hook: VfprintfHook) -> Self {
+        self.vfprintf = Some(hook);
+        self
+    }
+
+    pub fn exit(mut self, hook: ExitHook) -> Self {
+        self.exit = Some(hook);
+        self
+    }
+
+    fn option_strings(&self) -> Result<Vec<CString>, InvocationError> {
+        let mut options = Vec::new();
+
+        if !self.class_path.is_empty() {
+            let class_path = env::join_paths(&self.class_path).map_err(|_| VmError::Invalid)?;
+
+            options.push(format!("-Djava.class.path={}", class_path.to_string_lossy()));
+        }
+
+        for (key, value) in &self.properties {
+            options.push(format!("-D{}={}", key, value));
+        }
+
+        if self.check_jni {
+            options.push("-Xcheck:jni".to_owned());
+        }
+
+        options.extend(self.options.iter().cloned());
+
+        options
+            .into_iter()
+            .map(|o| CString::new(o).map_err(|_| InvocationError::Vm(VmError::Invalid)))
+            .collect()
+    }
+
+    pub fn build(self) -> Result<JavaVm, InvocationError> {
+        let library = JvmLibrary::get_or_load(self.library_path.as_deref())?;
+        let option_strings = self.option_strings()?;
+
+        let mut options = option_strings
+            .iter()
+            .map(|o| JavaVMOption {
+                optionString: o.as_ptr().cast_mut(),
+                extraInfo: null_mut(),
+            })
+            .collect::<Vec<_>>();
+        if let Some(hook) = self.vfprintf {
+            options.push(JavaVMOption {
+                optionString: c"vfprintf".as_ptr().cast_mut(),
+                extraInfo: hook as *mut c_void,
+            });
+        }
+        if let Some(hook) = self.exit {
+            options.push(JavaVMOption {
+                optionString: c"exit".as_ptr().cast_mut(),
+                extraInfo: hook as *mut c_void,
+            });
+        }
+
+        let mut args = JavaVMInitArgs {
+            version: self.version,
+            nOptions: options.len() as jint,
+            options: options.as_mut_ptr(),
+            ignoreUnrecognized: self.ignore_unrecognized,
+        };
+
+        let mut vm: *mut JavaVM = null_mut();
+        let mut env: *mut c_void = null_mut();
+        let code = unsafe { (library.create_java_vm)(&mut vm, &mut env, (&mut args as *mut JavaVMInitArgs).cast()) };
+        if code != JNI_OK {
+            return Err(VmError::from_code(code).into());
+        }
+
+        let vm = unsafe { JavaVm::from_raw(vm) };
+        set_java_vm(vm);
+
+        Ok(vm)
+    }
+}
+
+pub fn created_java_vms() -> Result<Vec<JavaVm>, InvocationError> {
+    let library = JvmLibrary::get_or_load(None)?;
+
+    let mut count: jsize = 0;
+    let code = unsafe { (library.get_created_java_vms)(null_mut(), 0, &mut count) };
+    if code != JNI_OK {
+        return Err(VmError::from_code(code).into());
+    }
+
+    let mut vms = vec![null_mut(); count as usize];
+    let code = unsafe { (library.get_created_java_vms)(vms.as_mut_ptr(), count, &mut count) };
+    if code != JNI_OK {
+        return Err(VmError::from_code(code).into());
+    }
+
+    Ok(vms
+        .into_iter()
+        .take(count as usize)
+        .map(|vm| unsafe { JavaVm::from_raw(vm) })
+        .collect())
+}
@@ -1,27 +1,27 @@
-use jni_sys::{jboolean, jbyte, jchar, jdouble, jfieldID, jfloat, jint, jlong, jmethodID, jobject, jshort, jvalue};
+use jni_sys::{jboolean, jbyte, jchar, jdouble, jfieldID, jfloat, jint, jlong, jmethodID, jshort, jvalue};
 use paste::paste;
-use std::ptr::null_mut;
 
-use crate::{call, Class, Context, FromJava, Object, Result};
+use crate::{call, Class, Context, FieldId, FromJava, MethodId, Object, Reference, Result};
 
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn invoke_with_throwable<R, F: FnOnce() -> R>(ctx: Context, f: F) -> Result<R> {
     let suppressed_throwable = unsafe { call!(v1_1, ctx, ExceptionOccurred) };
-    if suppressed_throwable != null_mut() {
+    if !suppressed_throwable.is_null() {
         unsafe { call!(v1_1, ctx, ExceptionClear) };
     }
 
     let r = f();
 
     let throwable = unsafe { call!(v1_1, ctx, ExceptionOccurred) };
-    if throwable != null_mut() {
+    if !throwable.is_null() {
         unsafe { call!(v1_1, ctx, ExceptionClear) };
     }
 
-    if suppressed_throwable != null_mut() {
+    if !suppressed_throwable.is_null() {
         unsafe { call!(v1_1, ctx, Throw, suppressed_throwable) };
     }
 
-    if throwable != null_mut() {
+    if !throwable.is_null() {
         Err(throwable)
     } else {
         Ok(r)
@@ -29,11 +29,29 @@ pub fn invoke_with_throwable<R, F: FnOnce() -> R>(ctx: Context, f: F) -> Result<
 }
 
 pub trait InvokeType {
+    /// # Safety
+    ///
+    /// `method` must be an instance method of `this` and `args` must match its signature.
     unsafe fn call_method(ctx: Context, this: Object, method: jmethodID, args: &[jvalue]) -> Self;
+    /// # Safety
+    ///
+    /// `method` must be a static method of `class` and `args` must match its signature.
     unsafe fn call_static_method(ctx: Context, class: Class, method: jmethodID, args: &[jvalue]) -> Self;
+    /// # Safety
+    ///
+    /// `field` must be an instance field of `this` whose java type is `Self`.
     unsafe fn get_field(ctx: Context, this: Object, field: jfieldID) -> Self;
+    /// # Safety
+    ///
+    /// `field` must be a static field of `class` whose java type is `Self`.
     unsafe fn get_static_field(ctx: Context, class: Class, field: jfieldID) -> Self;
+    /// # Safety
+    ///
+    /// `field` must be an instance field of `this` whose java type is `Self`.
     unsafe fn set_field(ctx: Context, this: Object, field: jfieldID, value: Self);
+    /// # Safety
+    ///
+    /// `field` must be a static field of `class` whose java type is `Self`.
     unsafe fn set_static_field(ctx: Context, class: Class, field: jfieldID, value: Self);
 }
 
@@ -91,7 +109,32 @@ impl_invoke_output!(jint, Int);
 impl_invoke_output!(jlong, Long);
 impl_invoke_output!(jfloat, Float);
 impl_invoke_output!(jdouble, Double);
-impl_invoke_output!(jobject, Object);
+
+impl<R: Reference> InvokeType for R {
+    unsafe fn call_method(ctx: Context, this: Object, method: jmethodID, args: &[jvalue]) -> Self {
+        R::from_object(call!(v1_1, ctx, CallObjectMethodA, this, method, args.as_ptr()))
+    }
+
+    unsafe fn call_static_method(ctx: Context, class: Class, method: jmethodID, args: &[jvalue]) -> Self {
+        R::from_object(call!(v1_1, ctx, CallStaticObjectMethodA, class, method, args.as_ptr()))
+    }
+
+    unsafe fn get_field(ctx: Context, this: Object, field: jfieldID) -> Self {
+        R::from_object(call!(v1_1, ctx, GetObjectField, this, field))
+    }
+
+    unsafe fn get_static_field(ctx: Context, class: Class, field: jfieldID) -> Self {
+        R::from_object(call!(v1_1, ctx, GetStaticObjectField, class, field))
+    }
+
+    unsafe fn set_field(ctx: Context, this: Object, field: jfieldID, value: Self) {
+        call!(v1_1, ctx, SetObjectField, this, field, value.as_object())
+    }
+
+    unsafe fn set_static_field(ctx: Context, class: Class, field: jfieldID, value: Self) {
+        call!(v1_1, ctx, SetStaticObjectField, class, field, value.as_object())
+    }
+}
 
 impl InvokeType for () {
     unsafe fn call_method(ctx: Context, this: Object, method: jmethodID, args: &[jvalue]) -> Self {
@@ -119,41 +162,72 @@ impl InvokeType for () {
     }
 }
 
+/// # Safety
+///
+/// `ctx` must be the env of the current thread, `method` an instance method of `this` returning `T` and
+/// `args` must match its signature.
 pub unsafe fn call_method<T: InvokeType, R: FromJava<T>>(
     ctx: Context,
     this: Object,
-    method: jmethodID,
+    method: MethodId,
     args: &[jvalue],
 ) -> Result<R> {
-    invoke_with_throwable(ctx, || T::call_method(ctx, this, method, args)).and_then(|o| R::from_java(o, ctx))
+    invoke_with_throwable(ctx, || T::call_method(ctx, this, method.as_raw(), args)).and_then(|o| R::from_java(o, ctx))
 }
 
+/// # Safety
+///
+/// `ctx` must be the env of the current thread, `method` a static method of `this` returning `T` and
+/// `args` must match its signature.
 pub unsafe fn call_static_method<T: InvokeType, R: FromJava<T>>(
     ctx: Context,
     this: Object,
-    method: jmethodID,
+    method: MethodId,
     args: &[jvalue],
 ) -> Result<R> {
-    invoke_with_throwable(ctx, || T::call_static_method(ctx, this, method, args)).and_then(|o| R::from_java(o, ctx))
+    invoke_with_throwable(ctx, || T::call_static_method(ctx, this, method.as_raw(), args)).and_then(|o| R::from_java(o, ctx))
 }
 
-pub unsafe fn get_field<T: InvokeType, R: FromJava<T>>(ctx: Context, this: Object, field: jfieldID) -> Result<R> {
-    invoke_with_throwable(ctx, || T::get_field(ctx, this, field)).and_then(|o| R::from_java(o, ctx))
+/// # Safety
+///
+/// `ctx` must be the env of the current thread and `field` an instance field of `this` of type `T`.
+pub unsafe fn get_field<T: InvokeType, R: FromJava<T>>(ctx: Context, this: Object, field: FieldId) -> Result<R> {
+    invoke_with_throwable(ctx, || T::get_field(ctx, this, field.as_raw())).and_then(|o| R::from_java(o, ctx))
 }
 
-pub unsafe fn get_static_field<T: InvokeType, R: FromJava<T>>(ctx: Context, class: Class, field: jfieldID) -> Result<R> {
-    invoke_with_throwable(ctx, || T::get_static_field(ctx, class, field)).and_then(|o| R::from_java(o, ctx))
+/// # Safety
+///
+/// `ctx` must be the env of the current thread and `field` a static field of `class` of type `T`.
+pub unsafe fn get_static_field<T: InvokeType, R: FromJava<T>>(ctx: Context, class: Class, field: FieldId) -> Result<R> {
+    invoke_with_throwable(ctx, || T::get_static_field(ctx, class, field.as_raw())).and_then(|o| R::from_java(o, ctx))
 }
 
-pub unsafe fn set_field<V: InvokeType>(ctx: Context, this: Object, field: jfieldID, value: V) -> Result<()> {
-    invoke_with_throwable(ctx, || V::set_field(ctx, this, field, value))
+/// # Safety
+///
+/// `ctx` must be the env of the current thread and `field` an instance field of `this` of type `V`.
+pub unsafe fn set_field<V: InvokeType>(ctx: Context, this: Object, field: FieldId, value: V) -> Result<()> {
+    invoke_with_throwable(ctx, || V::set_field(ctx, this, field.as_raw(), value))
 }
 
-pub unsafe fn set_static_field<V: InvokeType>(ctx: Context, class: Class, field: jfieldID, value: V) -> Result<()> {
-    invoke_with_throwable(ctx, || V::set_static_field(ctx, class, field, value))
+/// # Safety
+///
+/// `ctx` must be the env of the current thread and `field` a static field of `class` of type `V`.
+pub unsafe fn set_static_field<V: InvokeType>(ctx: Context, class: Class, field: FieldId, value: V) -> Result<()> {
+    invoke_with_throwable(ctx, || V::set_static_field(ctx, class, field.as_raw(), value))
 }
 
-pub unsafe fn new_object<R: FromJava<jobject>>(ctx: Context, class: Class, constructor: jmethodID, args: &[jvalue]) -> Result<R> {
-    invoke_with_throwable(ctx, || call!(v1_1, ctx, NewObjectA, class, constructor, args.as_ptr()))
-        .and_then(|o| R::from_java(o, ctx))
+/// # Safety
+///
+/// `ctx` must be the env of the current thread, `constructor` a constructor of `class` and `args` must
+/// match its signature. `T` must be a handle valid for instances of `class`.
+pub unsafe fn new_object<T: Reference, R: FromJava<T>>(
+    ctx: Context,
+    class: Class,
+    constructor: MethodId,
+    args: &[jvalue],
+) -> Result<R> {
+    invoke_with_throwable(ctx, || {
+        T::from_object(call!(v1_1, ctx, NewObjectA, class, constructor.as_raw(), args.as_ptr()))
+    })
+    .and_then(|o| R::from_java(o, ctx))
 }
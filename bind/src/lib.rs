@@ -1,24 +1,53 @@
 use jni_sys::{jclass, jobject, jthrowable, JNIEnv};
 
+pub use array::*;
 pub use bind::*;
-pub use bind_java_marco::bind_java;
+pub use bind_java_marco::{bind_java, native};
 pub use binding::*;
+pub use buffer::*;
+pub use collection::*;
+#[cfg(feature = "jni-compat")]
+pub use compat::*;
+pub use elements::*;
 pub use frame::*;
 pub use from::*;
-pub use func::*;
+pub use id::*;
 pub use into::*;
+#[cfg(feature = "invocation")]
+pub use invocation::*;
 pub use invoke::*;
+pub use jni_sys;
 pub use loader::*;
+pub use native::*;
+pub use onload::*;
+pub use reference::*;
+pub use throwable::*;
+pub use vm::*;
 
+mod array;
 mod bind;
 mod binding;
+mod boxed;
+mod buffer;
 mod call;
+mod collection;
+#[cfg(feature = "jni-compat")]
+mod compat;
+mod elements;
 mod frame;
 mod from;
 mod func;
+mod id;
 mod into;
+#[cfg(feature = "invocation")]
+mod invocation;
 mod invoke;
 mod loader;
+mod native;
+mod onload;
+mod reference;
+mod throwable;
+mod vm;
 
 pub type Context = *mut JNIEnv;
 pub type Class = jclass;
@@ -1,5 +1,581 @@
-use crate::{Class, Context, Result};
+use std::{
+    collections::{BTreeMap, HashMap},
+    ffi::CString,
+    path::Path,
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicI64, AtomicU8, Ordering},
+        Arc, Mutex, OnceLock, RwLock,
+    },
+};
+
+use jni_sys::{jbyte, jlong, jsize, jstring};
+
+use crate::{
+    binding::builtin_binding, call, call_method, call_static_method, define_class, find_class, find_field, find_method,
+    find_static_method, get_field, invoke_with_throwable, native_call, new_object, new_throwable, register_native_method,
+    with_pushed_frame, Class, Context, FieldId, FromJava, GlobalRef, IntoJava, IntoValue, Object, Result,
+};
+
+builtin_binding!(JavaClassLoader, "java.lang.ClassLoader", {
+    load_class: "loadClass" "(Ljava/lang/String;)Ljava/lang/Class;",
+}, static {
+    get_system_class_loader: "getSystemClassLoader" "()Ljava/lang/ClassLoader;",
+});
 
 pub trait ClassLoader {
     fn load_class(&self, ctx: Context, name: &str) -> Result<Class>;
+
+    // loaders without a java object act as the bootstrap loader wherever one is passed to java.
+    fn object(&self) -> Object {
+        null_mut()
+    }
+}
+
+pub struct ObjectClassLoader {
+    loader: GlobalRef,
+}
+
+impl ObjectClassLoader {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `loader` a live reference to a `java.lang.ClassLoader`.
+    pub unsafe fn new(ctx: Context, loader: Object) -> Result<Self> {
+        Ok(ObjectClassLoader {
+            loader: GlobalRef::new(ctx, loader)?,
+        })
+    }
+
+    unsafe fn from_local(ctx: Context, loader: Object) -> Result<Self> {
+        let result = ObjectClassLoader::new(ctx, loader);
+
+        call!(v1_1, ctx, DeleteLocalRef, loader);
+
+        result
+    }
+
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn system(ctx: Context) -> Result<Self> {
+        let b_loader = JavaClassLoader::bound(ctx)?;
+        let loader =
+            unsafe { call_static_method::<Object, Object>(ctx, b_loader.class(), b_loader.get_system_class_loader, &[])? };
+
+        unsafe { ObjectClassLoader::from_local(ctx, loader) }
+    }
+
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn context(ctx: Context) -> Result<Option<Self>> {
+        let loader = with_pushed_frame(ctx, 3, || {
+            let class = find_class(ctx, "java/lang/Thread")?;
+            let current_thread = find_static_method(ctx, class, "currentThread", "()Ljava/lang/Thread;")?;
+            let get_context_class_loader = find_method(ctx, class, "getContextClassLoader", "()Ljava/lang/ClassLoader;")?;
+
+            unsafe {
+                let thread = call_static_method::<Object, Object>(ctx, class, current_thread, &[])?;
+
+                call_method::<Object, Object>(ctx, thread, get_context_class_loader, &[])
+            }
+        })?;
+
+        if loader.is_null() {
+            Ok(None)
+        } else {
+            unsafe { ObjectClassLoader::from_local(ctx, loader).map(Some) }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and no copy of `object` may be used afterwards.
+    pub unsafe fn delete(self, ctx: Context) {
+        self.loader.delete(ctx)
+    }
+}
+
+impl ClassLoader for ObjectClassLoader {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    fn load_class(&self, ctx: Context, name: &str) -> Result<Class> {
+        let b_loader = JavaClassLoader::bound(ctx)?;
+
+        with_pushed_frame(ctx, 1, || {
+            let name: Object = name.replace('/', ".").into_java(ctx)?;
+
+            unsafe { call_method::<Class, Class>(ctx, self.loader.as_object(), b_loader.load_class, &[name.into_value()]) }
+        })
+    }
+
+    fn object(&self) -> Object {
+        self.loader.as_object()
+    }
+}
+
+pub struct UrlClassLoader {
+    loader: ObjectClassLoader,
+}
+
+impl UrlClassLoader {
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn from_urls(ctx: Context, urls: &[&str], parent: Option<&dyn ClassLoader>) -> Result<Self> {
+        UrlClassLoader::new(ctx, urls.len(), parent, |url_class| {
+            let constructor = find_method(ctx, url_class, "<init>", "(Ljava/lang/String;)V")?;
+
+            urls.iter()
+                .map(|url| {
+                    let url: Object = url.into_java(ctx)?;
+
+                    unsafe { new_object::<Object, Object>(ctx, url_class, constructor, &[url.into_value()]) }
+                })
+                .collect()
+        })
+    }
+
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn from_paths<P: AsRef<Path>>(ctx: Context, paths: &[P], parent: Option<&dyn ClassLoader>) -> Result<Self> {
+        UrlClassLoader::new(ctx, paths.len(), parent, |_| {
+            let file_class = find_class(ctx, "java/io/File")?;
+            let uri_class = find_class(ctx, "java/net/URI")?;
+            let constructor = find_method(ctx, file_class, "<init>", "(Ljava/lang/String;)V")?;
+            let to_uri = find_method(ctx, file_class, "toURI", "()Ljava/net/URI;")?;
+            let to_url = find_method(ctx, uri_class, "toURL", "()Ljava/net/URL;")?;
+
+            paths
+                .iter()
+                .map(|path| unsafe {
+                    let path: Object = path.as_ref().to_string_lossy().into_java(ctx)?;
+                    let file = new_object::<Object, Object>(ctx, file_class, constructor, &[path.into_value()])?;
+                    let uri = call_method::<Object, Object>(ctx, file, to_uri, &[])?;
+
+                    call_method::<Object, Object>(ctx, uri, to_url, &[])
+                })
+                .collect()
+        })
+    }
+
+    fn new<F: FnOnce(Class) -> Result<Vec<Object>>>(
+        ctx: Context,
+        count: usize,
+        parent: Option<&dyn ClassLoader>,
+        urls: F,
+    ) -> Result<Self> {
+        let loader = with_pushed_frame(ctx, count * 4 + 8, || {
+            let url_class = find_class(ctx, "java/net/URL")?;
+            let loader_class = find_class(ctx, "java/net/URLClassLoader")?;
+            let urls = urls(url_class)?;
+
+            unsafe {
+                let array = invoke_with_throwable(ctx, || {
+                    call!(v1_1, ctx, NewObjectArray, urls.len() as jsize, url_class, null_mut())
+                })?;
+                for (idx, url) in urls.into_iter().enumerate() {
+                    call!(v1_1, ctx, SetObjectArrayElement, array, idx as jsize, url);
+                }
+
+                match parent {
+                    Some(parent) => {
+                        let constructor = find_method(ctx, loader_class, "<init>", "([Ljava/net/URL;Ljava/lang/ClassLoader;)V")?;
+
+                        new_object::<Object, Object>(
+                            ctx,
+                            loader_class,
+                            constructor,
+                            &[array.into_value(), parent.object().into_value()],
+                        )
+                    }
+                    None => {
+                        let constructor = find_method(ctx, loader_class, "<init>", "([Ljava/net/URL;)V")?;
+
+                        new_object::<Object, Object>(ctx, loader_class, constructor, &[array.into_value()])
+                    }
+                }
+            }
+        })?;
+
+        Ok(UrlClassLoader {
+            loader: unsafe { ObjectClassLoader::from_local(ctx, loader)? },
+        })
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and no copy of `object` may be used afterwards.
+    pub unsafe fn delete(self, ctx: Context) {
+        self.loader.delete(ctx)
+    }
+}
+
+impl ClassLoader for UrlClassLoader {
+    fn load_class(&self, ctx: Context, name: &str) -> Result<Class> {
+        self.loader.load_class(ctx, name)
+    }
+
+    fn object(&self) -> Object {
+        self.loader.object()
+    }
+}
+
+// a SecureClassLoader whose native `findClass` serves the classes registered under its `classes` id.
+const MEMORY_LOADER_CLASS: &str = "bind_java/MemoryClassLoader";
+
+fn memory_loader_class_file() -> Vec<u8> {
+    let mut bytes = vec![0xca, 0xfe, 0xba, 0xbe, 0, 0, 0, 52];
+
+    let utf8 = |bytes: &mut Vec<u8>, value: &str| {
+        bytes.push(1);
+        bytes.extend((value.len() as u16).to_be_bytes());
+        bytes.extend(value.as_bytes());
+    };
+    let refs = |bytes: &mut Vec<u8>, tag: u8, refs: &[u16]| {
+        bytes.push(tag);
+        refs.iter().for_each(|r| bytes.extend(r.to_be_bytes()));
+    };
+
+    bytes.extend(17u16.to_be_bytes());
+    utf8(&mut bytes, MEMORY_LOADER_CLASS); // #1
+    refs(&mut bytes, 7, &[1]); // #2 this class
+    utf8(&mut bytes, "java/security/SecureClassLoader"); // #3
+    refs(&mut bytes, 7, &[3]); // #4 super class
+    utf8(&mut bytes, "<init>"); // #5
+    utf8(&mut bytes, "(Ljava/lang/ClassLoader;)V"); // #6
+    refs(&mut bytes, 12, &[5, 6]); // #7
+    refs(&mut bytes, 10, &[4, 7]); // #8 super constructor
+    utf8(&mut bytes, "classes"); // #9
+    utf8(&mut bytes, "J"); // #10
+    refs(&mut bytes, 12, &[9, 10]); // #11
+    refs(&mut bytes, 9, &[2, 11]); // #12 classes field
+    utf8(&mut bytes, "(Ljava/lang/ClassLoader;J)V"); // #13
+    utf8(&mut bytes, "findClass"); // #14
+    utf8(&mut bytes, "(Ljava/lang/String;)Ljava/lang/Class;"); // #15
+    utf8(&mut bytes, "Code"); // #16
+
+    // public final, this, super, no interfaces
+    [0x0031u16, 2, 4, 0].iter().for_each(|v| bytes.extend(v.to_be_bytes()));
+    // private final long classes
+    [1u16, 0x0012, 9, 10, 0].iter().for_each(|v| bytes.extend(v.to_be_bytes()));
+
+    bytes.extend(2u16.to_be_bytes());
+    // public <init>(ClassLoader parent, long classes) { super(parent); this.classes = classes; }
+    [0x0001u16, 5, 13, 1, 16].iter().for_each(|v| bytes.extend(v.to_be_bytes()));
+    bytes.extend(23u32.to_be_bytes());
+    [3u16, 4].iter().for_each(|v| bytes.extend(v.to_be_bytes()));
+    bytes.extend(11u32.to_be_bytes());
+    bytes.extend([0x2a, 0x2b, 0xb7, 0, 8, 0x2a, 0x20, 0xb5, 0, 12, 0xb1]);
+    bytes.extend([0, 0, 0, 0]);
+    // protected native Class findClass(String name)
+    [0x0104u16, 14, 15, 0].iter().for_each(|v| bytes.extend(v.to_be_bytes()));
+
+    bytes.extend(0u16.to_be_bytes());
+
+    bytes
+}
+
+struct MemoryLoaderClass {
+    class: GlobalRef,
+    classes: FieldId,
+}
+
+// safe to share, the class is only used through global refs and ids.
+unsafe impl Send for MemoryLoaderClass {}
+unsafe impl Sync for MemoryLoaderClass {}
+
+static MEMORY_LOADER: OnceLock<MemoryLoaderClass> = OnceLock::new();
+static MEMORY_LOADER_INIT: Mutex<()> = Mutex::new(());
+
+type MemoryClasses = Arc<HashMap<String, Vec<u8>>>;
+
+static MEMORY_CLASSES: Mutex<BTreeMap<jlong, MemoryClasses>> = Mutex::new(BTreeMap::new());
+static NEXT_MEMORY_ID: AtomicI64 = AtomicI64::new(1);
+
+extern "system" fn find_memory_class(ctx: Context, this: Object, name: jstring) -> Class {
+    unsafe {
+        native_call(ctx, "java/lang/RuntimeException", || {
+            let name = String::from_java(name, ctx)?;
+            let loader = memory_loader_class(ctx)?;
+            let id = get_field::<jlong, jlong>(ctx, this, loader.classes)?;
+
+            let classes = MEMORY_CLASSES.lock().unwrap().get(&id).cloned();
+            let internal_name = name.replace('.', "/");
+            let Some(bytes) = classes.as_ref().and_then(|c| c.get(&internal_name)) else {
+                return Err(new_throwable(ctx, "java/lang/ClassNotFoundException", &name));
+            };
+
+            let internal_name = CString::new(internal_name).unwrap();
+
+            invoke_with_throwable(ctx, || {
+                call!(
+                    v1_1,
+                    ctx,
+                    DefineClass,
+                    internal_name.as_ptr(),
+                    this,
+                    bytes.as_ptr().cast(),
+                    bytes.len() as jsize
+                )
+            })
+        })
+    }
+}
+
+fn memory_loader_class(ctx: Context) -> Result<&'static MemoryLoaderClass> {
+    if let Some(loader) = MEMORY_LOADER.get() {
+        return Ok(loader);
+    }
+
+    // defining the class twice fails, so only one thread may try.
+    let _init = MEMORY_LOADER_INIT.lock().unwrap();
+    if let Some(loader) = MEMORY_LOADER.get() {
+        return Ok(loader);
+    }
+
+    let class = define_class(ctx, MEMORY_LOADER_CLASS, None, &memory_loader_class_file())?;
+    let loader = (|| -> Result<MemoryLoaderClass> {
+        register_native_method(
+            ctx,
+            class,
+            "findClass",
+            "(Ljava/lang/String;)Ljava/lang/Class;",
+            find_memory_class as *const (),
+        )?;
+
+        Ok(MemoryLoaderClass {
+            classes: find_field(ctx, class, "classes", "J")?,
+            class: unsafe { GlobalRef::new(ctx, class)? },
+        })
+    })();
+
+    unsafe { call!(v1_1, ctx, DeleteLocalRef, class) };
+
+    let loader = loader?;
+
+    Ok(MEMORY_LOADER.get_or_init(|| loader))
+}
+
+pub struct InMemoryClassLoader {
+    loader: ObjectClassLoader,
+    id: jlong,
+}
+
+impl InMemoryClassLoader {
+    // classes are defined by `findClass` once they are first loaded, after the parent failed to load them.
+    #[allow(clippy::not_unsafe_ptr_arg_deref)]
+    pub fn new<I, N>(ctx: Context, classes: I, parent: Option<&dyn ClassLoader>) -> Result<Self>
+    where
+        I: IntoIterator<Item = (N, Vec<u8>)>,
+        N: AsRef<str>,
+    {
+        let classes = classes
+            .into_iter()
+            .map(|(name, bytes)| (name.as_ref().replace('.', "/"), bytes))
+            .collect::<HashMap<_, _>>();
+        let memory_loader = memory_loader_class(ctx)?;
+        let id = NEXT_MEMORY_ID.fetch_add(1, Ordering::Relaxed);
+
+        let loader = with_pushed_frame(ctx, 2, || {
+            let parent = match parent {
+                Some(parent) => parent.object(),
+                None => {
+                    let b_loader = JavaClassLoader::bound(ctx)?;
+
+                    unsafe { call_static_method::<Object, Object>(ctx, b_loader.class(), b_loader.get_system_class_loader, &[])? }
+                }
+            };
+            let class = memory_loader.class.as_object();
+            let constructor = find_method(ctx, class, "<init>", "(Ljava/lang/ClassLoader;J)V")?;
+
+            unsafe { new_object::<Object, Object>(ctx, class, constructor, &[parent.into_value(), id.into_value()]) }
+        })?;
+
+        MEMORY_CLASSES.lock().unwrap().insert(id, Arc::new(classes));
+
+        Ok(InMemoryClassLoader {
+            loader: unsafe { ObjectClassLoader::from_local(ctx, loader)? },
+            id,
+        })
+    }
+
+    pub fn from_jar(ctx: Context, jar: &[u8], parent: Option<&dyn ClassLoader>) -> Result<Self> {
+        InMemoryClassLoader::new(ctx, read_jar_classes(ctx, jar)?, parent)
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and no copy of `object` may be used afterwards. Classes
+    /// already defined stay usable, the remaining ones can no longer be loaded.
+    pub unsafe fn delete(self, ctx: Context) {
+        MEMORY_CLASSES.lock().unwrap().remove(&self.id);
+
+        self.loader.delete(ctx)
+    }
+}
+
+impl ClassLoader for InMemoryClassLoader {
+    fn load_class(&self, ctx: Context, name: &str) -> Result<Class> {
+        self.loader.load_class(ctx, name)
+    }
+
+    fn object(&self) -> Object {
+        self.loader.object()
+    }
+}
+
+fn read_jar_classes(ctx: Context, jar: &[u8]) -> Result<Vec<(String, Vec<u8>)>> {
+    let stream = with_pushed_frame(ctx, 4, || {
+        let input_class = find_class(ctx, "java/io/ByteArrayInputStream")?;
+        let input_constructor = find_method(ctx, input_class, "<init>", "([B)V")?;
+        let zip_class = find_class(ctx, "java/util/zip/ZipInputStream")?;
+        let zip_constructor = find_method(ctx, zip_class, "<init>", "(Ljava/io/InputStream;)V")?;
+
+        let bytes: Object = unsafe { std::slice::from_raw_parts(jar.as_ptr().cast::<jbyte>(), jar.len()) }.into_java(ctx)?;
+
+        unsafe {
+            let input = new_object::<Object, Object>(ctx, input_class, input_constructor, &[bytes.into_value()])?;
+
+            new_object::<Object, Object>(ctx, zip_class, zip_constructor, &[input.into_value()])
+        }
+    })?;
+
+    let classes = (|| {
+        let zip_class = find_class(ctx, "java/util/zip/ZipInputStream")?;
+        let entry_class = find_class(ctx, "java/util/zip/ZipEntry")?;
+        let next_entry = find_method(ctx, zip_class, "getNextEntry", "()Ljava/util/zip/ZipEntry;")?;
+        let read_all_bytes = find_method(ctx, zip_class, "readAllBytes", "()[B")?;
+        let get_name = find_method(ctx, entry_class, "getName", "()Ljava/lang/String;")?;
+
+        unsafe {
+            call!(v1_1, ctx, DeleteLocalRef, zip_class);
+            call!(v1_1, ctx, DeleteLocalRef, entry_class);
+        }
+
+        let mut classes = Vec::new();
+        loop {
+            let entry = unsafe { call_method::<Object, Object>(ctx, stream, next_entry, &[])? };
+            if entry.is_null() {
+                break;
+            }
+
+            let name = unsafe { call_method::<Object, String>(ctx, entry, get_name, &[]) };
+            unsafe { call!(v1_1, ctx, DeleteLocalRef, entry) };
+
+            let Some(name) = name?.strip_suffix(".class").map(|n| n.to_owned()) else {
+                continue;
+            };
+            if name.ends_with("module-info") {
+                continue;
+            }
+
+            let bytes = unsafe { call_method::<Object, Vec<jbyte>>(ctx, stream, read_all_bytes, &[])? };
+
+            classes.push((name, bytes.into_iter().map(|b| b as u8).collect()));
+        }
+
+        Ok(classes)
+    })();
+
+    unsafe { call!(v1_1, ctx, DeleteLocalRef, stream) };
+
+    classes
+}
+
+static REGISTERED_LOADERS: RwLock<Vec<Arc<dyn ClassLoader + Send + Sync>>> = RwLock::new(Vec::new());
+
+pub fn register_class_loader<L: ClassLoader + Send + Sync + 'static>(loader: Arc<L>) {
+    REGISTERED_LOADERS.write().unwrap().push(loader);
+}
+
+pub fn unregister_class_loader<L: ClassLoader + Send + Sync + 'static>(loader: &Arc<L>) -> bool {
+    let mut loaders = REGISTERED_LOADERS.write().unwrap();
+    let count = loaders.len();
+
+    loaders.retain(|l| !std::ptr::addr_eq(Arc::as_ptr(l), Arc::as_ptr(loader)));
+
+    loaders.len() != count
+}
+
+pub fn register_jar(ctx: Context, jar: &[u8]) -> Result<Arc<InMemoryClassLoader>> {
+    let loader = Arc::new(InMemoryClassLoader::from_jar(ctx, jar, None)?);
+
+    register_class_loader(loader.clone());
+
+    Ok(loader)
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ClassLoaderFallback {
+    Disabled,
+    Registered,
+    AppClassLoader,
+}
+
+static FALLBACK: AtomicU8 = AtomicU8::new(ClassLoaderFallback::AppClassLoader as u8);
+
+pub fn set_class_loader_fallback(fallback: ClassLoaderFallback) {
+    FALLBACK.store(fallback as u8, Ordering::Relaxed);
+}
+
+pub fn class_loader_fallback() -> ClassLoaderFallback {
+    match FALLBACK.load(Ordering::Relaxed) {
+        0 => ClassLoaderFallback::Disabled,
+        1 => ClassLoaderFallback::Registered,
+        _ => ClassLoaderFallback::AppClassLoader,
+    }
+}
+
+static APP_CLASS_LOADER: OnceLock<ObjectClassLoader> = OnceLock::new();
+
+// only the context class loader of a thread entered from java, such as `JNI_OnLoad` or a native method, is the loader
+// of the application. threads attached from native code only see the system loader, so this must not be called from them.
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn capture_app_class_loader(ctx: Context) -> Result<&'static ObjectClassLoader> {
+    if let Some(loader) = APP_CLASS_LOADER.get() {
+        return Ok(loader);
+    }
+
+    let loader = match ObjectClassLoader::context(ctx)? {
+        Some(loader) => loader,
+        None => ObjectClassLoader::system(ctx)?,
+    };
+
+    if let Err(loader) = APP_CLASS_LOADER.set(loader) {
+        unsafe { loader.delete(ctx) };
+    }
+
+    Ok(APP_CLASS_LOADER.get().unwrap())
+}
+
+pub fn app_class_loader() -> Option<&'static ObjectClassLoader> {
+    APP_CLASS_LOADER.get()
+}
+
+// natives are always entered from java, the first one captures the loader when the library was not loaded by `JNI_OnLoad`.
+pub(crate) fn capture_app_class_loader_on_entry(ctx: Context) {
+    if APP_CLASS_LOADER.get().is_some() || class_loader_fallback() != ClassLoaderFallback::AppClassLoader {
+        return;
+    }
+
+    if let Err(throwable) = capture_app_class_loader(ctx) {
+        unsafe { call!(v1_1, ctx, DeleteLocalRef, throwable) };
+    }
+}
+
+pub(crate) fn load_with_fallback(ctx: Context, name: &str) -> Option<Class> {
+    let fallback = class_loader_fallback();
+    if fallback == ClassLoaderFallback::Disabled {
+        return None;
+    }
+
+    let mut loaders = Vec::<&dyn ClassLoader>::new();
+    if fallback == ClassLoaderFallback::AppClassLoader {
+        loaders.extend(app_class_loader().map(|l| l as &dyn ClassLoader));
+    }
+
+    let registered = REGISTERED_LOADERS.read().unwrap().clone();
+    loaders.extend(registered.iter().map(|l| &**l as &dyn ClassLoader));
+
+    loaders.into_iter().find_map(|loader| match loader.load_class(ctx, name) {
+        Ok(class) => Some(class),
+        Err(throwable) => {
+            unsafe { call!(v1_1, ctx, DeleteLocalRef, throwable) };
+
+            None
+        }
+    })
 }
@@ -0,0 +1,149 @@
+use std::{
+    any::Any,
+    ffi::CString,
+    panic::{catch_unwind, AssertUnwindSafe},
+    ptr::null_mut,
+};
+
+use jni_sys::{
+    jboolean, jbooleanArray, jbyte, jbyteArray, jchar, jcharArray, jdouble, jdoubleArray, jfloat, jfloatArray, jint, jintArray,
+    jlong, jlongArray, jobjectArray, jshort, jshortArray, jstring,
+};
+
+use crate::{call, capture_app_class_loader_on_entry, Context, Reference, Result};
+
+pub trait ZeroValue {
+    fn zero() -> Self;
+}
+
+macro_rules! zero_impl {
+    ($typ:ty, $zero:expr) => {
+        impl ZeroValue for $typ {
+            fn zero() -> Self {
+                $zero
+            }
+        }
+    };
+}
+
+zero_impl!(jboolean, false);
+zero_impl!(jbyte, 0);
+zero_impl!(jchar, 0);
+zero_impl!(jshort, 0);
+zero_impl!(jint, 0);
+zero_impl!(jlong, 0);
+zero_impl!(jfloat, 0.0);
+zero_impl!(jdouble, 0.0);
+zero_impl!((), ());
+
+impl<T> ZeroValue for Option<T> {
+    fn zero() -> Self {
+        None
+    }
+}
+
+impl<R: Reference> ZeroValue for R {
+    fn zero() -> Self {
+        unsafe { R::from_object(null_mut()) }
+    }
+}
+
+pub trait NativeType {
+    type Raw: ZeroValue;
+}
+
+macro_rules! native_impl {
+    ($typ:ty, $raw:ty) => {
+        impl NativeType for $typ {
+            type Raw = $raw;
+        }
+    };
+}
+
+native_impl!(jboolean, jboolean);
+native_impl!(jbyte, jbyte);
+// jchar is u16, so unsigned utf-16 units are covered as well.
+native_impl!(jchar, jchar);
+native_impl!(jshort, jshort);
+native_impl!(jint, jint);
+native_impl!(jlong, jlong);
+native_impl!(jfloat, jfloat);
+native_impl!(jdouble, jdouble);
+native_impl!(char, jchar);
+native_impl!(u8, jbyte);
+native_impl!((), ());
+native_impl!(String, jstring);
+native_impl!(Vec<jboolean>, jbooleanArray);
+native_impl!(Vec<jbyte>, jbyteArray);
+native_impl!(Vec<u8>, jbyteArray);
+native_impl!(Vec<jchar>, jcharArray);
+native_impl!(Vec<jshort>, jshortArray);
+native_impl!(Vec<jint>, jintArray);
+native_impl!(Vec<jlong>, jlongArray);
+native_impl!(Vec<jfloat>, jfloatArray);
+native_impl!(Vec<jdouble>, jdoubleArray);
+native_impl!(Vec<String>, jobjectArray);
+
+impl<R: Reference> NativeType for R {
+    type Raw = R;
+}
+
+impl<T: NativeType> NativeType for Option<T> {
+    type Raw = T::Raw;
+}
+
+unsafe fn throw_panic(ctx: Context, exception: &str, payload: Box<dyn Any + Send>) {
+    let message = if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "unknown panic".to_owned()
+    };
+
+    let exception = CString::new(exception).unwrap();
+    let message = CString::new(format!("panic in native code: {}", message).replace('\0', "")).unwrap();
+
+    let class = call!(v1_1, ctx, FindClass, exception.as_ptr());
+    if !class.is_null() {
+        call!(v1_1, ctx, ThrowNew, class, message.as_ptr());
+        call!(v1_1, ctx, DeleteLocalRef, class);
+    }
+}
+
+/// # Safety
+///
+/// Must be called on the thread `ctx` belongs to, from a native method entered from java: a panic is thrown to
+/// the caller as `exception`, which must name a throwable class, and zero returned.
+pub unsafe fn catch_panic<T: ZeroValue, F: FnOnce() -> T>(ctx: Context, exception: &str, f: F) -> T {
+    let f = || {
+        capture_app_class_loader_on_entry(ctx);
+
+        f()
+    };
+
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(value) => value,
+        Err(payload) => {
+            throw_panic(ctx, exception, payload);
+
+            T::zero()
+        }
+    }
+}
+
+/// # Safety
+///
+/// Must be called on the thread `ctx` belongs to, from a native method entered from java: the `Err` of `f` is
+/// thrown to the caller and zero returned.
+pub unsafe fn native_call<T: ZeroValue, F: FnOnce() -> Result<T>>(ctx: Context, exception: &str, f: F) -> T {
+    match catch_panic(ctx, exception, || Some(f())) {
+        Some(Ok(value)) => value,
+        Some(Err(throwable)) => {
+            call!(v1_1, ctx, Throw, throwable);
+
+            T::zero()
+        }
+        None => T::zero(),
+    }
+}
@@ -0,0 +1,103 @@
+use std::{ffi::c_void, ptr::null_mut};
+
+use jni_sys::{
+    jint, JavaVM, JNI_ERR, JNI_OK, JNI_VERSION_10, JNI_VERSION_19, JNI_VERSION_1_2, JNI_VERSION_1_4, JNI_VERSION_1_6,
+    JNI_VERSION_1_8, JNI_VERSION_20, JNI_VERSION_21, JNI_VERSION_24, JNI_VERSION_9,
+};
+
+use crate::{call, capture_app_class_loader, catch_panic, set_java_vm, Context, JavaVm, Result};
+
+const SUPPORTED_VERSIONS: [jint; 10] = [
+    JNI_VERSION_24,
+    JNI_VERSION_21,
+    JNI_VERSION_20,
+    JNI_VERSION_19,
+    JNI_VERSION_10,
+    JNI_VERSION_9,
+    JNI_VERSION_1_8,
+    JNI_VERSION_1_6,
+    JNI_VERSION_1_4,
+    JNI_VERSION_1_2,
+];
+
+/// # Safety
+///
+/// `vm` must be a live vm and the current thread attached to it.
+pub unsafe fn negotiate_version(vm: *mut JavaVM) -> Option<(Context, jint)> {
+    SUPPORTED_VERSIONS.iter().find_map(|&version| {
+        let mut env: *mut c_void = null_mut();
+
+        if call!(v1_2, vm, GetEnv, &mut env, version) == JNI_OK {
+            Some((env.cast(), version))
+        } else {
+            None
+        }
+    })
+}
+
+/// # Safety
+///
+/// Must only be called from `JNI_OnLoad` with the vm it was passed.
+pub unsafe fn on_load<F: FnOnce(Context) -> Result<()>>(vm: *mut JavaVM, init: F) -> jint {
+    let Some((ctx, version)) = negotiate_version(vm) else {
+        return JNI_ERR;
+    };
+
+    set_java_vm(JavaVm::from_raw(vm));
+
+    if let Err(throwable) = capture_app_class_loader(ctx) {
+        call!(v1_1, ctx, DeleteLocalRef, throwable);
+    }
+
+    match catch_panic(ctx, "java/lang/RuntimeException", || Some(init(ctx))) {
+        Some(Ok(())) => version,
+        Some(Err(throwable)) => {
+            call!(v1_1, ctx, Throw, throwable);
+
+            JNI_ERR
+        }
+        None => JNI_ERR,
+    }
+}
+
+/// # Safety
+///
+/// Must only be called from `JNI_OnUnload` with the vm it was passed.
+pub unsafe fn on_unload<F: FnOnce(Context)>(vm: *mut JavaVM, deinit: F) {
+    // without an env there is nothing to tear down against, the library state is left as is.
+    let Some((ctx, _)) = negotiate_version(vm) else {
+        return;
+    };
+
+    catch_panic(ctx, "java/lang/RuntimeException", || deinit(ctx));
+}
+
+#[macro_export]
+macro_rules! jni_onload {
+    (|$ctx:tt| $init:block) => {
+        $crate::jni_onload!(|$ctx| $init, |_| {});
+    };
+    (|$ctx:tt| $init:block, |$unload_ctx:tt| $unload:block) => {
+        #[no_mangle]
+        pub extern "system" fn JNI_OnLoad(vm: *mut $crate::jni_sys::JavaVM, _: *mut ::std::ffi::c_void) -> $crate::jni_sys::jint {
+            let __init = |__ctx: $crate::Context| -> $crate::Result<()> {
+                let $ctx: $crate::Context = __ctx;
+
+                $init
+            };
+
+            unsafe { $crate::on_load(vm, __init) }
+        }
+
+        #[no_mangle]
+        pub extern "system" fn JNI_OnUnload(vm: *mut $crate::jni_sys::JavaVM, _: *mut ::std::ffi::c_void) {
+            let __deinit = |__ctx: $crate::Context| {
+                let $unload_ctx: $crate::Context = __ctx;
+
+                $unload
+            };
+
+            unsafe { $crate::on_unload(vm, __deinit) }
+        }
+    };
+}
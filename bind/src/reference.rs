@@ -0,0 +1,161 @@
+use std::ptr::null_mut;
+
+use crate::{call, invoke_with_throwable, new_throwable, Class, Context, Object, Result};
+
+/// # Safety
+///
+/// Implementors must be `#[repr(transparent)]` over `Object`.
+pub unsafe trait Reference: Copy {
+    fn as_object(self) -> Object;
+
+    /// # Safety
+    ///
+    /// `object` must be null or a reference to an instance of the java type `Self` stands for.
+    unsafe fn from_object(object: Object) -> Self;
+}
+
+unsafe impl Reference for Object {
+    fn as_object(self) -> Object {
+        self
+    }
+
+    unsafe fn from_object(object: Object) -> Self {
+        object
+    }
+}
+
+macro_rules! handle_impl {
+    ($handle:ident) => {
+        #[repr(transparent)]
+        #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+        pub struct $handle(Object);
+
+        unsafe impl Reference for $handle {
+            fn as_object(self) -> Object {
+                self.0
+            }
+
+            unsafe fn from_object(object: Object) -> Self {
+                $handle(object)
+            }
+        }
+
+        impl FromJava<$handle> for Object {
+            unsafe fn from_java(value: $handle, _: Context) -> Result<Self> {
+                Ok(value.0)
+            }
+        }
+
+        impl From<$handle> for Object {
+            fn from(value: $handle) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+pub(crate) use handle_impl;
+
+/// # Safety
+///
+/// `ctx` must be the env of the current thread, `object` a live reference or null and `class` a live reference.
+pub unsafe fn is_instance_of(ctx: Context, object: Object, class: Class) -> bool {
+    call!(v1_1, ctx, IsInstanceOf, object, class)
+}
+
+pub struct GlobalRef {
+    object: Object,
+}
+
+// global references are valid on every thread until deleted.
+unsafe impl Send for GlobalRef {}
+unsafe impl Sync for GlobalRef {}
+
+impl GlobalRef {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `object` a live reference or null.
+    pub unsafe fn new(ctx: Context, object: Object) -> Result<Self> {
+        let object = invoke_with_throwable(ctx, || call!(v1_1, ctx, NewGlobalRef, object))?;
+
+        Ok(GlobalRef { object })
+    }
+
+    pub fn as_object(&self) -> Object {
+        self.object
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and no copy of `as_object` may be used afterwards.
+    pub unsafe fn delete(self, ctx: Context) {
+        call!(v1_1, ctx, DeleteGlobalRef, self.object)
+    }
+}
+
+pub struct WeakRef {
+    object: Object,
+}
+
+// weak global references are valid on every thread until deleted.
+unsafe impl Send for WeakRef {}
+unsafe impl Sync for WeakRef {}
+
+impl WeakRef {
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `object` a live reference or null.
+    pub unsafe fn new(ctx: Context, object: Object) -> Result<Self> {
+        let object = invoke_with_throwable(ctx, || call!(v1_2, ctx, NewWeakGlobalRef, object))?;
+
+        Ok(WeakRef { object })
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread.
+    pub unsafe fn is_collected(&self, ctx: Context) -> bool {
+        call!(v1_1, ctx, IsSameObject, self.object, null_mut())
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread and `object` a live reference or null.
+    pub unsafe fn is_same_object(&self, ctx: Context, object: Object) -> bool {
+        !self.is_collected(ctx) && call!(v1_1, ctx, IsSameObject, self.object, object)
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread, the returned local reference is owned by the caller.
+    pub unsafe fn upgrade(&self, ctx: Context) -> Option<Object> {
+        let object = call!(v1_2, ctx, NewLocalRef, self.object);
+
+        if object.is_null() {
+            None
+        } else {
+            Some(object)
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread, the upgraded reference is deleted once `f` returns.
+    pub unsafe fn with_upgraded<R, F: FnOnce(Object) -> Result<R>>(&self, ctx: Context, f: F) -> Result<R> {
+        let object = self
+            .upgrade(ctx)
+            .ok_or_else(|| new_throwable(ctx, "java/lang/IllegalStateException", "reference has been collected"))?;
+        let result = f(object);
+
+        call!(v1_1, ctx, DeleteLocalRef, object);
+
+        result
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread.
+    pub unsafe fn delete(self, ctx: Context) {
+        call!(v1_2, ctx, DeleteWeakGlobalRef, self.object)
+    }
+}
@@ -0,0 +1,21 @@
+use std::ffi::CString;
+
+use crate::{call, find_class, invoke_with_throwable, Context, Throwable};
+
+#[allow(clippy::not_unsafe_ptr_arg_deref)]
+pub fn new_throwable(ctx: Context, internal_class_name: &str, message: &str) -> Throwable {
+    let class = match find_class(ctx, internal_class_name) {
+        Ok(class) => class,
+        Err(throwable) => return throwable,
+    };
+    let message = CString::new(message.replace('\0', "\\0")).unwrap();
+
+    let throwable = unsafe { invoke_with_throwable(ctx, || call!(v1_1, ctx, ThrowNew, class, message.as_ptr())) };
+
+    unsafe { call!(v1_1, ctx, DeleteLocalRef, class) };
+
+    match throwable {
+        Ok(_) => panic!("ThrowNew did not throw"),
+        Err(throwable) => throwable,
+    }
+}
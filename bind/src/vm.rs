@@ -0,0 +1,245 @@
+use std::{
+    cell::Cell,
+    error::Error,
+    ffi::{c_void, CString},
+    fmt::{Display, Formatter},
+    marker::PhantomData,
+    ptr::null_mut,
+    sync::atomic::{AtomicPtr, Ordering},
+};
+
+use jni_sys::{
+    jint, JavaVM, JavaVMAttachArgs, JNI_EDETACHED, JNI_EEXIST, JNI_EINVAL, JNI_ENOMEM, JNI_EVERSION, JNI_OK, JNI_VERSION_1_8,
+};
+
+use crate::{call, Context, Object};
+
+static JAVA_VM: AtomicPtr<JavaVM> = AtomicPtr::new(null_mut());
+
+thread_local! {
+    static CURRENT_ENV: Cell<Context> = const { Cell::new(null_mut()) };
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum VmError {
+    NoVm,
+    Detached,
+    Version,
+    NoMemory,
+    Exists,
+    Invalid,
+    Unknown(jint),
+}
+
+impl VmError {
+    pub fn from_code(code: jint) -> Self {
+        match code {
+            JNI_EDETACHED => VmError::Detached,
+            JNI_EVERSION => VmError::Version,
+            JNI_ENOMEM => VmError::NoMemory,
+            JNI_EEXIST => VmError::Exists,
+            JNI_EINVAL => VmError::Invalid,
+            code => VmError::Unknown(code),
+        }
+    }
+
+    fn check(code: jint) -> std::result::Result<(), VmError> {
+        if code == JNI_OK {
+            Ok(())
+        } else {
+            Err(VmError::from_code(code))
+        }
+    }
+}
+
+impl Display for VmError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VmError::NoVm => f.write_str("no java vm available"),
+            VmError::Detached => f.write_str("current thread is not attached to the java vm"),
+            VmError::Version => f.write_str("unsupported jni version"),
+            VmError::NoMemory => f.write_str("not enough memory"),
+            VmError::Exists => f.write_str("java vm already created"),
+            VmError::Invalid => f.write_str("invalid arguments"),
+            VmError::Unknown(code) => write!(f, "unknown jni error {}", code),
+        }
+    }
+}
+
+impl Error for VmError {}
+
+#[derive(Default)]
+pub struct AttachOptions<'a> {
+    pub name: Option<&'a str>,
+    pub group: Option<Object>,
+    pub daemon: bool,
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct JavaVm {
+    vm: *mut JavaVM,
+}
+
+// the invocation interface may be used from any thread.
+unsafe impl Send for JavaVm {}
+unsafe impl Sync for JavaVm {}
+
+impl JavaVm {
+    /// # Safety
+    ///
+    /// `vm` must be a live vm that outlives every use of the handle.
+    pub unsafe fn from_raw(vm: *mut JavaVM) -> Self {
+        JavaVm { vm }
+    }
+
+    /// # Safety
+    ///
+    /// `ctx` must be the env of the current thread.
+    pub unsafe fn from_env(ctx: Context) -> std::result::Result<Self, VmError> {
+        let mut vm = null_mut();
+        VmError::check(call!(v1_1, ctx, GetJavaVM, &mut vm))?;
+
+        Ok(JavaVm { vm })
+    }
+
+    pub fn global() -> std::result::Result<Self, VmError> {
+        java_vm().ok_or(VmError::NoVm)
+    }
+
+    pub fn as_raw(&self) -> *mut JavaVM {
+        self.vm
+    }
+
+    pub fn get_env(&self) -> std::result::Result<Context, VmError> {
+        let mut env: *mut c_void = null_mut();
+        VmError::check(unsafe { call!(v1_2, self.vm, GetEnv, &mut env, JNI_VERSION_1_8) })?;
+
+        Ok(env.cast())
+    }
+
+    pub fn attach_current_thread(&self) -> std::result::Result<AttachGuard, VmError> {
+        self.attach_current_thread_with(AttachOptions::default())
+    }
+
+    pub fn attach_current_thread_with(&self, options: AttachOptions) -> std::result::Result<AttachGuard, VmError> {
+        if let Ok(env) = self.get_env() {
+            return Ok(AttachGuard {
+                vm: *self,
+                env,
+                detach: false,
+                _thread: PhantomData,
+            });
+        }
+
+        let env = unsafe { self.attach(&options)? };
+
+        // daemon threads are detached as well, the flag only keeps them from blocking vm shutdown.
+        Ok(AttachGuard {
+            vm: *self,
+            env,
+            detach: true,
+            _thread: PhantomData,
+        })
+    }
+
+    pub fn attach_as_daemon(&self) -> std::result::Result<AttachGuard, VmError> {
+        self.attach_current_thread_with(AttachOptions {
+            daemon: true,
+            ..AttachOptions::default()
+        })
+    }
+
+    unsafe fn attach(&self, options: &AttachOptions) -> std::result::Result<Context, VmError> {
+        let name = options.name.map(|n| CString::new(n).unwrap());
+        let mut args = JavaVMAttachArgs {
+            version: JNI_VERSION_1_8,
+            name: name.as_ref().map_or(null_mut(), |n| n.as_ptr().cast_mut()),
+            group: options.group.unwrap_or(null_mut()),
+        };
+
+        let mut env: *mut c_void = null_mut();
+        let args_ptr = (&mut args as *mut JavaVMAttachArgs).cast();
+        if options.daemon {
+            VmError::check(call!(v1_4, self.vm, AttachCurrentThreadAsDaemon, &mut env, args_ptr))?;
+        } else {
+            VmError::check(call!(v1_1, self.vm, AttachCurrentThread, &mut env, args_ptr))?;
+        }
+
+        CURRENT_ENV.set(env.cast());
+
+        Ok(env.cast())
+    }
+
+    /// # Safety
+    ///
+    /// No reference, id or env obtained from the vm may be used afterwards, on any thread.
+    pub unsafe fn destroy(self) -> std::result::Result<(), VmError> {
+        CURRENT_ENV.set(null_mut());
+
+        let _ = JAVA_VM.compare_exchange(self.vm, null_mut(), Ordering::AcqRel, Ordering::Acquire);
+
+        VmError::check(call!(v1_1, self.vm, DestroyJavaVM))
+    }
+
+    /// # Safety
+    ///
+    /// The thread must have been attached by this handle outside of an `AttachGuard` and no local reference of
+    /// its env may be used afterwards. Threads entered from java must not detach.
+    pub unsafe fn detach_current_thread(&self) -> std::result::Result<(), VmError> {
+        CURRENT_ENV.set(null_mut());
+
+        VmError::check(call!(v1_1, self.vm, DetachCurrentThread))
+    }
+}
+
+pub struct AttachGuard {
+    vm: JavaVm,
+    env: Context,
+    detach: bool,
+    _thread: PhantomData<*const ()>,
+}
+
+impl AttachGuard {
+    pub fn env(&self) -> Context {
+        self.env
+    }
+
+    pub fn vm(&self) -> JavaVm {
+        self.vm
+    }
+}
+
+impl Drop for AttachGuard {
+    fn drop(&mut self) {
+        if self.detach {
+            unsafe {
+                let _ = self.vm.detach_current_thread();
+            }
+        }
+    }
+}
+
+pub fn set_java_vm(vm: JavaVm) {
+    JAVA_VM.store(vm.as_raw(), Ordering::Release);
+}
+
+pub fn java_vm() -> Option<JavaVm> {
+    let vm = JAVA_VM.load(Ordering::Acquire);
+
+    if vm.is_null() {
+        None
+    } else {
+        Some(JavaVm { vm })
+    }
+}
+
+// only envs of threads attached through JavaVm are cached, their detach goes through detach_current_thread as well.
+pub fn current_env() -> Option<Context> {
+    let env = CURRENT_ENV.get();
+
+    if !env.is_null() {
+        return Some(env);
+    }
+
+    java_vm()?.get_env().ok()
+}
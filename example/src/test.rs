@@ -1,10 +1,22 @@
-use std::process::Stdio;
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    process::Stdio,
+    ptr::null_mut,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
 
-use jni_sys::{jint, jobject, jstring};
+use jni_sys::{jbooleanArray, jint, jintArray, jobject, jobjectArray, jstring, JNI_ERR, JNI_VERSION_1_8};
 use proc_macro2::TokenStream;
 use quote::quote;
 
-use bind_java::{bind_java, call, system_fn, Class, ClassBinding, ClassLoader, Context, FromJava, IntoJava, Object, WithClass};
+use bind_java::{
+    bind_java, call, get_array_region, set_array_region, system_fn, ArrayElements, AttachOptions, BorrowedBuffer, ByteBufferRef,
+    Class, ClassBinding, ClassLoader, Context, CriticalArray, FromJava, InMemoryClassLoader, IntoJava, JavaVm, JavaVmBuilder,
+    Object, ObjectClassLoader, PrimitiveArrayRef, Reference, ReleaseMode, UrlClassLoader, VmError, WeakRef, WithClass,
+};
 
 use crate::vm::with_java_vm;
 
@@ -47,7 +59,8 @@ pub fn test_convert_string_array() {
             })
             .collect::<Vec<_>>();
 
-        let o_array = array.clone().into_java(env).unwrap();
+        // Vec<T> converts to lists and typed object arrays as well, so the raw array type has to be named.
+        let o_array: jobjectArray = array.clone().into_java(env).unwrap();
         let r_array = unsafe { Vec::<String>::from_java(o_array, env).unwrap() };
 
         assert_eq!(array, r_array);
@@ -60,49 +73,29 @@ pub fn test_convert_bool_array() {
         let length: usize = rand::random::<usize>() % 128;
         let array: Vec<bool> = (0..length).map(|_| rand::random::<bool>()).collect();
 
-        let o_array = array.clone().into_java(env).unwrap();
+        let o_array: jbooleanArray = array.clone().into_java(env).unwrap();
         let r_array = unsafe { Vec::<bool>::from_java(o_array, env).unwrap() };
 
         assert_eq!(array, r_array);
     })
 }
 
-bind_java! {
-    @ClassName("java.io.File")
-    class JavaFile {
-        JavaFile(java.lang.String path);
-
-        java.net.URI toURI();
-    }
-
-    @ClassName("java.net.URI")
-    class JavaURI {
-        java.net.URL toURL();
-    }
-
-    @ClassName("java.net.URLClassLoader")
-    class JavaUrlClassLoader {
-        static java.net.URLClassLoader newInstance(java.net.URL[] urls);
-
-        java.lang.Class loadClass(java.lang.String name);
-    }
-}
-
-struct UrlClassLoader {
+struct CompiledClasses {
     _class_path: tempdir::TempDir,
-    binding: JavaUrlClassLoader,
-    object: Object,
+    loader: UrlClassLoader,
 }
 
-impl ClassLoader for UrlClassLoader {
+impl ClassLoader for CompiledClasses {
     fn load_class(&self, ctx: Context, name: &str) -> bind_java::Result<Class> {
-        let name = name.replace("/", ".");
+        self.loader.load_class(ctx, name)
+    }
 
-        unsafe { self.binding.load_class(ctx, self.object, name) }
+    fn object(&self) -> Object {
+        self.loader.object()
     }
 }
 
-fn compile_file_and_load_classes(ctx: Context, public_class_name: &str, content: TokenStream) -> UrlClassLoader {
+fn compile_file_and_load_classes(ctx: Context, public_class_name: &str, content: TokenStream) -> CompiledClasses {
     let temp = tempdir::TempDir::new("classes").unwrap();
     let file_content = content.to_string().replace(" . ", ".").replace(" $ ", "$");
     let file = temp.path().join(public_class_name).with_extension("java");
@@ -123,26 +116,11 @@ fn compile_file_and_load_classes(ctx: Context, public_class_name: &str, content:
         panic!("compile java failed");
     }
 
-    unsafe {
-        let c_file = JavaFile::find_class(ctx, None).unwrap();
-        let b_file = JavaFile::bind(ctx, c_file).unwrap();
-        let o_file = b_file.new(ctx, c_file, temp.path().to_str().unwrap()).unwrap();
-
-        let c_uri = JavaURI::find_class(ctx, None).unwrap();
-        let b_uri = JavaURI::bind(ctx, c_uri).unwrap();
-        let o_uri: jobject = b_file.to_uri(ctx, o_file).unwrap();
-
-        let c_url = call!(v1_1, ctx, FindClass, "java/net/URL\0".as_ptr().cast());
-        let o_url_array = call!(v1_1, ctx, NewObjectArray, 1, c_url, b_uri.to_url(ctx, o_uri).unwrap());
-        let c_url_class_loader = JavaUrlClassLoader::find_class(ctx, None).unwrap();
-        let b_url_class_loader = JavaUrlClassLoader::bind(ctx, c_url_class_loader).unwrap();
-        let o_url_class_loader: jobject = b_url_class_loader.new_instance(ctx, c_url_class_loader, o_url_array).unwrap();
+    let loader = UrlClassLoader::from_paths(ctx, &[temp.path()], None).unwrap();
 
-        UrlClassLoader {
-            _class_path: temp,
-            binding: b_url_class_loader,
-            object: o_url_class_loader,
-        }
+    CompiledClasses {
+        _class_path: temp,
+        loader,
     }
 }
 
@@ -226,8 +204,348 @@ pub fn test_register_native() {
             )
             .unwrap();
 
-            assert_eq!(b_test.call_native::<jint>(env, c_test, 114514).unwrap(), 114515);
-            assert_eq!(b_test.native_call::<jint>(env, c_test, 1919810).unwrap(), 1919811);
+            assert_eq!(b_test.call_native(env, c_test, 114514).unwrap(), 114515);
+            assert_eq!(b_test.native_call(env, c_test, 1919810).unwrap(), 1919811);
+        }
+    });
+}
+
+#[test]
+pub fn test_register_natives() {
+    with_java_vm(|env| {
+        let loader = compile_file_and_load_classes(
+            env,
+            "RustNativesTest",
+            quote! {
+                public class RustNativesTest {
+                    private static native int add(int a, int b);
+                    private native String greet(String name);
+                    private static native void fail();
+
+                    public static int callAdd(int a, int b) {
+                        return add(a, b);
+                    }
+
+                    public String callGreet(String name) {
+                        return greet(name);
+                    }
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustNativesTest")
+            class RustNativesTest {
+                RustNativesTest();
+
+                static native int add(int a, int b);
+                native java.lang.String greet(java.lang.String name);
+                static native void fail();
+
+                static int callAdd(int a, int b);
+                java.lang.String callGreet(java.lang.String name);
+            }
+        }
+
+        struct Natives;
+
+        impl RustNativesTestNatives for Natives {
+            fn add(_: Context, _: Class, a: i32, b: i32) -> bind_java::Result<i32> {
+                Ok(a + b)
+            }
+
+            fn greet(_: Context, this: RustNativesTestRef, name: Option<String>) -> bind_java::Result<String> {
+                assert!(!this.as_object().is_null());
+
+                Ok(format!("hello, {}", name.as_deref().unwrap_or("nobody")))
+            }
+
+            fn fail(ctx: Context, _: Class) -> bind_java::Result<()> {
+                Err(bind_java::new_throwable(
+                    ctx,
+                    "java/lang/IllegalStateException",
+                    "failed in native",
+                ))
+            }
+        }
+
+        unsafe {
+            let c_test = RustNativesTest::find_class(env, Some(&loader)).unwrap();
+            let b_test = RustNativesTest::bind(env, c_test).unwrap();
+
+            RustNativesTest::register_natives::<Natives>(env, c_test).unwrap();
+
+            let o_test = b_test.new(env, c_test).unwrap();
+
+            assert_eq!(b_test.call_add(env, c_test, 40, 2).unwrap(), 42);
+            assert_eq!(b_test.call_greet(env, o_test, "natives").unwrap(), "hello, natives");
+            assert_eq!(b_test.call_greet(env, o_test, None::<&str>).unwrap(), "hello, nobody");
+            assert!(b_test.fail(env, c_test).is_err());
+        }
+    });
+}
+
+#[test]
+pub fn test_native_panic() {
+    with_java_vm(|env| {
+        let loader = compile_file_and_load_classes(
+            env,
+            "RustPanicTest",
+            quote! {
+                public class RustPanicTest {
+                    private static native int panicking(int value);
+                    private static native int failing(int value);
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustPanicTest")
+            @PanicException("java.lang.IllegalStateException")
+            class RustPanicTest {
+                static native int panicking(int value);
+                static native int failing(int value);
+            }
+        }
+
+        struct Natives;
+
+        impl RustPanicTestNatives for Natives {
+            fn panicking(_: Context, _: Class, value: i32) -> bind_java::Result<i32> {
+                panic!("panicking with {}", value)
+            }
+
+            fn failing(ctx: Context, _: Class, _: i32) -> bind_java::Result<i32> {
+                Err(bind_java::new_throwable(ctx, "java/lang/IllegalArgumentException", "failing"))
+            }
+        }
+
+        unsafe {
+            let c_test = RustPanicTest::find_class(env, Some(&loader)).unwrap();
+            let b_test = RustPanicTest::bind(env, c_test).unwrap();
+
+            let is_instance = |throwable: Object, class_name: &str| {
+                let class = bind_java::find_class(env, class_name).unwrap();
+
+                bind_java::is_instance_of(env, throwable, class)
+            };
+
+            RustPanicTest::register_natives::<Natives>(env, c_test).unwrap();
+
+            let throwable = b_test.panicking(env, c_test, 1).unwrap_err();
+            assert!(is_instance(throwable, "java/lang/IllegalStateException"));
+
+            let throwable = b_test.failing(env, c_test, 1).unwrap_err();
+            assert!(is_instance(throwable, "java/lang/IllegalArgumentException"));
+
+            RustPanicTest::register_panicking(
+                env,
+                c_test,
+                system_fn!(|_: Context, _: Class, value: jint| -> jint { panic!("panicking with {}", value) }),
+            )
+            .unwrap();
+
+            let throwable = b_test.panicking(env, c_test, 2).unwrap_err();
+            assert!(is_instance(throwable, "java/lang/RuntimeException"));
+            assert!(!is_instance(throwable, "java/lang/IllegalStateException"));
+
+            RustPanicTest::register_panicking(
+                env,
+                c_test,
+                system_fn!(throws "java/lang/UnsupportedOperationException", |_: Context, _: Class, value: jint| -> jint {
+                    panic!("panicking with {}", value)
+                }),
+            )
+            .unwrap();
+
+            let throwable = b_test.panicking(env, c_test, 3).unwrap_err();
+            assert!(is_instance(throwable, "java/lang/UnsupportedOperationException"));
+
+            RustPanicTest::register_failing(
+                env,
+                c_test,
+                system_fn!(|ctx: Context, _: Class, value: jint| -> Result<jint> {
+                    if value < 0 {
+                        Err(bind_java::new_throwable(
+                            ctx,
+                            "java/lang/IllegalArgumentException",
+                            "negative",
+                        ))
+                    } else {
+                        Ok(value)
+                    }
+                }),
+            )
+            .unwrap();
+
+            assert_eq!(b_test.failing(env, c_test, 4).unwrap(), 4);
+            let throwable = b_test.failing(env, c_test, -1).unwrap_err();
+            assert!(is_instance(throwable, "java/lang/IllegalArgumentException"));
+        }
+    });
+}
+
+#[test]
+pub fn test_native_fn() {
+    with_java_vm(|env| {
+        let loader = compile_file_and_load_classes(
+            env,
+            "RustNativeFnTest",
+            quote! {
+                public class RustNativeFnTest {
+                    private static native String greet(String name, int[] data);
+                    private static native int explode(String value);
+                    private static native byte[] reverse(byte[] data);
+                    private static native char shift(char value, int by);
+                    private static native int widen(byte value);
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustNativeFnTest")
+            class RustNativeFnTest {
+                static native java.lang.String greet(java.lang.String name, int[] data);
+                static native int explode(java.lang.String value);
+                static native byte[] reverse(byte[] data);
+                static native char shift(char value, int by);
+                static native int widen(byte value);
+            }
+        }
+
+        #[bind_java::native]
+        fn greet(_: Context, _: Class, name: String, data: Vec<jint>) -> bind_java::Result<String> {
+            Ok(format!("{}: {}", name, data.iter().sum::<jint>()))
+        }
+
+        #[bind_java::native(exception = "java.lang.IllegalStateException")]
+        fn explode(_: Context, _: Class, value: Option<String>) -> jint {
+            panic!("exploded with {:?}", value)
+        }
+
+        #[bind_java::native]
+        fn reverse(_: Context, _: Class, data: Vec<u8>) -> Vec<u8> {
+            data.into_iter().rev().collect()
+        }
+
+        #[bind_java::native]
+        fn shift(_: Context, _: Class, value: char, by: jint) -> char {
+            char::from_u32(value as u32 + by as u32).unwrap()
+        }
+
+        #[bind_java::native]
+        fn widen(_: Context, _: Class, value: u8) -> jint {
+            value as jint * 2
+        }
+
+        unsafe {
+            let c_test = RustNativeFnTest::find_class(env, Some(&loader)).unwrap();
+            let b_test = RustNativeFnTest::bind(env, c_test).unwrap();
+
+            RustNativeFnTest::register_greet(env, c_test, greet).unwrap();
+            RustNativeFnTest::register_explode(env, c_test, explode).unwrap();
+            RustNativeFnTest::register_reverse(env, c_test, reverse).unwrap();
+
+            let value = b_test.greet(env, c_test, "sum", &[1, 2, 3][..]).unwrap();
+            assert_eq!(value, "sum: 6");
+
+            let throwable = b_test.explode(env, c_test, None::<String>).unwrap_err();
+            let c_exception = bind_java::find_class(env, "java/lang/IllegalStateException").unwrap();
+            assert!(bind_java::is_instance_of(env, throwable, c_exception));
+
+            let reversed: Vec<u8> = b_test.reverse_as(env, c_test, vec![1u8, 2, 255]).unwrap();
+            assert_eq!(reversed, vec![255, 2, 1]);
+
+            RustNativeFnTest::register_shift(env, c_test, shift).unwrap();
+            RustNativeFnTest::register_widen(env, c_test, widen).unwrap();
+
+            let c_illegal = bind_java::find_class(env, "java/lang/IllegalArgumentException").unwrap();
+            assert_eq!(b_test.shift(env, c_test, 'a', 1).unwrap(), 'b');
+            let throwable = b_test.shift(env, c_test, 'a', 0x1f600 - 'a' as jint).unwrap_err();
+            assert!(bind_java::is_instance_of(env, throwable, c_illegal));
+            let throwable = b_test.shift(env, c_test, 0xd800u16, 0).unwrap_err();
+            assert!(bind_java::is_instance_of(env, throwable, c_illegal));
+
+            assert_eq!(b_test.widen(env, c_test, 255u8).unwrap(), 510);
+        }
+    });
+}
+
+#[test]
+pub fn test_exported_natives() {
+    with_java_vm(|env| {
+        let loader = compile_file_and_load_classes(
+            env,
+            "RustExport_Test",
+            quote! {
+                public class RustExport_Test {
+                    public static class Inner {
+                        private static native int value();
+                    }
+
+                    private static native int add_one(int value);
+                    private static native int twice(int value);
+                    private static native String twice(String value);
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustExport_Test")
+            @Export("Exported")
+            class RustExportTest {
+                static native int add_one(int value);
+
+                @Alias("twice_int")
+                static native int twice(int value);
+
+                @Alias("twice_string")
+                static native java.lang.String twice(java.lang.String value);
+            }
+
+            @ClassName("RustExport_Test$Inner")
+            @Export("Exported")
+            class RustExportTestInner {
+                static native int value();
+            }
+        }
+
+        struct Exported;
+
+        impl RustExportTestNatives for Exported {
+            fn add_one(_: Context, _: Class, value: i32) -> bind_java::Result<i32> {
+                Ok(value + 1)
+            }
+
+            fn twice_int(_: Context, _: Class, value: i32) -> bind_java::Result<i32> {
+                Ok(value * 2)
+            }
+
+            fn twice_string(_: Context, _: Class, value: Option<String>) -> bind_java::Result<String> {
+                Ok(value.unwrap_or_default().repeat(2))
+            }
+        }
+
+        impl RustExportTestInnerNatives for Exported {
+            fn value(_: Context, _: Class) -> bind_java::Result<i32> {
+                Ok(42)
+            }
+        }
+
+        unsafe {
+            let c_test = RustExportTest::find_class(env, Some(&loader)).unwrap();
+            let c_inner = RustExportTestInner::find_class(env, Some(&loader)).unwrap();
+
+            assert_eq!(Java_RustExport_1Test_add_1one(env, c_test, 1), 2);
+            assert_eq!(Java_RustExport_1Test_twice__I(env, c_test, 21), 42);
+            assert_eq!(Java_RustExport_1Test_00024Inner_value(env, c_inner), 42);
+
+            let o_value = Java_RustExport_1Test_twice__Ljava_lang_String_2(env, c_test, "ab".into_java(env).unwrap());
+            assert_eq!(String::from_java(o_value, env).unwrap(), "abab");
+
+            let b_test = RustExportTest::bind(env, c_test).unwrap();
+            RustExportTest::register_add_one(env, c_test, Java_RustExport_1Test_add_1one).unwrap();
+            assert_eq!(b_test.add_one(env, c_test, 41).unwrap(), 42);
         }
     });
 }
@@ -246,7 +564,7 @@ pub fn test_boolean_parameter() {
         unsafe {
             let c_atomic_boolean = JavaAtomicBoolean::find_class(ctx, None).unwrap();
             let b_atomic_boolean = JavaAtomicBoolean::bind(ctx, c_atomic_boolean).unwrap();
-            let o_atomic_boolean: jobject = b_atomic_boolean.new(ctx, c_atomic_boolean, true).unwrap();
+            let o_atomic_boolean = b_atomic_boolean.new(ctx, c_atomic_boolean, true).unwrap();
 
             let success: bool = b_atomic_boolean.compare_and_set(ctx, o_atomic_boolean, true, false).unwrap();
             assert!(success);
@@ -256,3 +574,1307 @@ pub fn test_boolean_parameter() {
         }
     });
 }
+
+#[test]
+pub fn test_constant_field() {
+    with_java_vm(|ctx| {
+        bind_java! {
+            @ClassName("java.lang.Integer")
+            class JavaInteger {
+                static final int MAX_VALUE = 2147483647;
+                static final int MIN_VALUE = -2147483648;
+            }
+
+            @ClassName("java.io.File")
+            class JavaFileSeparator {
+                static final char separatorChar = '/';
+                static final java.lang.String separator = "/";
+            }
+
+            @ClassName("java.lang.Long")
+            class JavaLongMismatch {
+                static final long MAX_VALUE = 0;
+            }
+        }
+
+        assert_eq!(JavaInteger::MAX_VALUE, jint::MAX);
+        assert_eq!(JavaInteger::MIN_VALUE, jint::MIN);
+        assert_eq!(JavaFileSeparator::SEPARATOR, "/");
+
+        unsafe {
+            let c_integer = JavaInteger::find_class(ctx, None).unwrap();
+            JavaInteger::bind(ctx, c_integer).unwrap();
+
+            let c_file = JavaFileSeparator::find_class(ctx, None).unwrap();
+            JavaFileSeparator::bind(ctx, c_file).unwrap();
+
+            let c_long = JavaLongMismatch::find_class(ctx, None).unwrap();
+            assert_eq!(JavaLongMismatch::bind(ctx, c_long).is_err(), cfg!(debug_assertions));
+        }
+    });
+}
+
+#[test]
+pub fn test_constant_primitive_fields() {
+    with_java_vm(|ctx| {
+        let loader = compile_file_and_load_classes(
+            ctx,
+            "RustConstantTest",
+            quote! {
+                public class RustConstantTest {
+                    public static final boolean ENABLED = true;
+                    public static final float RATIO = 1.5f;
+                    public static final double SCALE = -2.25;
+                    public static final char LETTER = 'x';
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustConstantTest")
+            class JavaRustConstantTest {
+                static final boolean ENABLED = true;
+                static final float RATIO = 1.5;
+                static final double SCALE = -2.25;
+                static final char LETTER = 'x';
+            }
+
+            @ClassName("RustConstantTest")
+            class JavaRustConstantMismatch {
+                static final double SCALE = 2.25;
+            }
+        }
+
+        const { assert!(JavaRustConstantTest::ENABLED) };
+        assert_eq!(JavaRustConstantTest::RATIO, 1.5);
+        assert_eq!(JavaRustConstantTest::SCALE, -2.25);
+        assert_eq!(JavaRustConstantTest::LETTER, 'x');
+
+        JavaRustConstantTest::bound_in(ctx, &loader).unwrap();
+        assert_eq!(
+            JavaRustConstantMismatch::bound_in(ctx, &loader).is_err(),
+            cfg!(debug_assertions)
+        );
+    });
+}
+
+#[test]
+pub fn test_binding_singleton() {
+    bind_java! {
+        @ClassName("java.lang.Integer")
+        class JavaIntegerParser {
+            static int parseInt(java.lang.String s);
+        }
+    }
+
+    let bound = with_java_vm(|ctx| JavaIntegerParser::bound(ctx).unwrap());
+
+    let threads = (0..4)
+        .map(|idx| {
+            std::thread::spawn(move || {
+                with_java_vm(|ctx| unsafe {
+                    let b_integer = JavaIntegerParser::bound(ctx).unwrap();
+                    let value: jint = b_integer.parse_int(ctx, b_integer.class(), idx.to_string()).unwrap();
+
+                    assert_eq!(value, idx);
+
+                    b_integer as *const _ as usize
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), bound as *const _ as usize);
+    }
+}
+
+#[test]
+pub fn test_lazy_binding() {
+    with_java_vm(|ctx| {
+        bind_java! {
+            @Lazy
+            @ClassName("java.lang.StringBuilder")
+            class JavaLazyStringBuilder {
+                JavaLazyStringBuilder();
+
+                java.lang.StringBuilder append(java.lang.String value);
+                java.lang.String toString();
+                void methodThatDoesNotExist();
+            }
+        }
+
+        unsafe {
+            let c_builder = JavaLazyStringBuilder::find_class(ctx, None).unwrap();
+            let b_builder = JavaLazyStringBuilder::bind(ctx, c_builder).unwrap();
+
+            let o_builder = b_builder.new(ctx, c_builder).unwrap();
+            b_builder.append(ctx, o_builder, "lazy").unwrap();
+            let _: jobject = b_builder.append_as(ctx, o_builder, " binding").unwrap();
+            let value: String = b_builder.to_string(ctx, o_builder).unwrap();
+
+            assert_eq!(value, "lazy binding");
+            assert!(b_builder.method_that_does_not_exist(ctx, o_builder).is_err());
+            assert!(b_builder.method_that_does_not_exist(ctx, o_builder).is_err());
+
+            b_builder.unbind(ctx);
+        }
+    });
+}
+
+#[test]
+pub fn test_lazy_binding_releases_loader() {
+    with_java_vm(|ctx| {
+        let loader = compile_file_and_load_classes(
+            ctx,
+            "RustLazyLoaderTest",
+            quote! {
+                public class RustLazyLoaderTest {
+                    public static int answer() {
+                        return 42;
+                    }
+                }
+            },
+        );
+
+        bind_java! {
+            @Lazy
+            @ClassName("RustLazyLoaderTest")
+            class JavaRustLazyLoaderTest {
+                static int answer();
+            }
+
+            @ClassName("java.lang.System")
+            class JavaLazySystem {
+                static void gc();
+            }
+        }
+
+        unsafe {
+            let b_test = JavaRustLazyLoaderTest::bound_in(ctx, &loader).unwrap();
+            assert_eq!(b_test.answer(ctx, b_test.class()).unwrap(), 42);
+            drop(b_test);
+
+            let weak = WeakRef::new(ctx, loader.object()).unwrap();
+            loader.loader.delete(ctx);
+
+            let statics = JavaLazySystemRef::statics(ctx).unwrap();
+            let collected = (0..10).any(|_| {
+                statics.gc().unwrap();
+                weak.is_collected(ctx)
+            });
+            weak.delete(ctx);
+
+            assert!(collected);
+        }
+    });
+}
+
+#[test]
+pub fn test_loader_binding_cache() {
+    with_java_vm(|env| {
+        let first = compile_file_and_load_classes(
+            env,
+            "RustLoaderTest",
+            quote! {
+                public class RustLoaderTest {
+                    public static String NAME = "first";
+
+                    public String name() {
+                        return NAME;
+                    }
+                }
+            },
+        );
+        let second = compile_file_and_load_classes(
+            env,
+            "RustLoaderTest",
+            quote! {
+                public class RustLoaderTest {
+                    public static String NAME = "second";
+
+                    public String name() {
+                        return NAME;
+                    }
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustLoaderTest")
+            class JavaRustLoaderTest {
+                JavaRustLoaderTest();
+
+                static java.lang.String NAME;
+
+                java.lang.String name();
+            }
+        }
+
+        unsafe {
+            let b_first = JavaRustLoaderTest::bound_in(env, &first).unwrap();
+            let b_first_again = JavaRustLoaderTest::bound_in(env, &first).unwrap();
+            let b_second = JavaRustLoaderTest::bound_in(env, &second).unwrap();
+
+            assert!(std::ptr::eq(&*b_first, &*b_first_again));
+            assert!(!std::ptr::eq(&*b_first, &*b_second));
+
+            let first_name: String = b_first.get_name(env, b_first.class()).unwrap();
+            let second_name: String = b_second.get_name(env, b_second.class()).unwrap();
+
+            assert_eq!(first_name, "first");
+            assert_eq!(second_name, "second");
+
+            let statics = JavaRustLoaderTestRef::statics_in(env, &b_second);
+            assert_eq!(statics.get_name().unwrap(), "second");
+
+            let o_second = statics.new().unwrap();
+            assert_eq!(o_second.on_in(env, &b_second).name().unwrap(), "second");
+            assert!(JavaRustLoaderTestRef::statics(env).is_err());
+        }
+
+        let third = compile_file_and_load_classes(
+            env,
+            "RustLoaderTest",
+            quote! {
+                public class RustLoaderTest {
+                    public static String NAME = "third";
+
+                    public String name() {
+                        return NAME;
+                    }
+                }
+            },
+        );
+        let bindings = std::thread::scope(|scope| {
+            let threads = (0..8)
+                .map(|_| {
+                    scope.spawn(|| {
+                        with_java_vm(|env| {
+                            let b_third = JavaRustLoaderTest::bound_in(env, &third).unwrap();
+
+                            &*b_third as *const JavaRustLoaderTest as usize
+                        })
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            threads.into_iter().map(|t| t.join().unwrap()).collect::<Vec<_>>()
+        });
+        let b_third = JavaRustLoaderTest::bound_in(env, &third).unwrap();
+
+        assert!(bindings.iter().all(|&b| b == &*b_third as *const JavaRustLoaderTest as usize));
+
+        struct DelegatingLoader<'a>(&'a CompiledClasses);
+
+        impl ClassLoader for DelegatingLoader<'_> {
+            fn load_class(&self, ctx: Context, name: &str) -> bind_java::Result<Class> {
+                self.0.load_class(ctx, name)
+            }
+        }
+
+        let delegating = DelegatingLoader(&third);
+        let b_delegating = JavaRustLoaderTest::bound_in(env, &delegating).unwrap();
+        let b_delegating_again = JavaRustLoaderTest::bound_in(env, &delegating).unwrap();
+
+        assert!(std::ptr::eq(&*b_delegating, &*b_delegating_again));
+        unsafe {
+            assert_eq!(
+                JavaRustLoaderTestRef::statics_in(env, &b_delegating).get_name().unwrap(),
+                "third"
+            );
+        }
+    });
+}
+
+#[test]
+pub fn test_typed_handle() {
+    with_java_vm(|ctx| {
+        bind_java! {
+            @ClassName("java.lang.StringBuilder")
+            class JavaTypedStringBuilder {
+                JavaTypedStringBuilder(java.lang.String value);
+
+                java.lang.StringBuilder reverse();
+                java.lang.String toString();
+            }
+
+            @ClassName("java.lang.Integer")
+            class JavaTypedInteger {
+                static java.lang.Integer valueOf(int value);
+            }
+        }
+
+        unsafe {
+            let b_builder = JavaTypedStringBuilder::bound(ctx).unwrap();
+            let b_integer = JavaTypedInteger::bound(ctx).unwrap();
+
+            let o_builder: JavaTypedStringBuilderRef = b_builder.new(ctx, b_builder.class(), "handle").unwrap();
+            let o_reversed = b_builder.reverse(ctx, o_builder).unwrap();
+            let value: String = b_builder.to_string(ctx, o_reversed).unwrap();
+
+            assert_eq!(value, "eldnah");
+
+            let o_object: Object = o_builder.into();
+            assert_eq!(JavaTypedStringBuilderRef::downcast(ctx, o_object).unwrap(), Some(o_builder));
+            assert_eq!(JavaTypedIntegerRef::downcast(ctx, o_object).unwrap(), None);
+
+            let o_integer: JavaTypedIntegerRef = b_integer.value_of(ctx, b_integer.class(), 42).unwrap();
+            assert!(JavaTypedStringBuilderRef::downcast(ctx, o_integer.into()).unwrap().is_none());
+        }
+    });
+}
+
+#[test]
+pub fn test_bound_views() {
+    with_java_vm(|ctx| {
+        bind_java! {
+            @ClassName("java.util.ArrayList")
+            class JavaViewList {
+                JavaViewList();
+
+                boolean add(java.lang.Object value);
+                int size();
+            }
+
+            @ClassName("java.lang.Integer")
+            class JavaViewInteger {
+                static final int MAX_VALUE;
+
+                static java.lang.String toHexString(int value);
+            }
+        }
+
+        unsafe {
+            let o_list = JavaViewListRef::statics(ctx).unwrap().new().unwrap();
+            let list = o_list.on(ctx).unwrap();
+
+            let added: bool = list.add("first").unwrap();
+            assert!(added);
+            let _: bool = list.add("second").unwrap();
+
+            let size: jint = list.size().unwrap();
+            assert_eq!(size, 2);
+            assert_eq!(list.this(), o_list);
+
+            let statics = JavaViewIntegerRef::statics(ctx).unwrap();
+            let max: jint = statics.get_max_value().unwrap();
+            let hex: String = statics.to_hex_string(255).unwrap();
+
+            assert_eq!(max, jint::MAX);
+            assert_eq!(hex, "ff");
+        }
+    });
+}
+
+#[test]
+pub fn test_default_return_types() {
+    with_java_vm(|ctx| {
+        bind_java! {
+            @ClassName("java.lang.StringBuilder")
+            class JavaDefaultStringBuilder {
+                JavaDefaultStringBuilder();
+
+                java.lang.StringBuilder append(java.lang.String value);
+                java.lang.StringBuilder reverse();
+                int length();
+                java.lang.String toString();
+            }
+
+            @ClassName("java.lang.String")
+            class JavaDefaultString {
+                static java.lang.String valueOf(boolean value);
+
+                boolean isEmpty();
+                char[] toCharArray();
+            }
+        }
+
+        unsafe {
+            let b_builder = JavaDefaultStringBuilder::bound(ctx).unwrap();
+            let o_builder = b_builder.new(ctx, b_builder.class()).unwrap();
+
+            let o_builder = b_builder.append(ctx, o_builder, "default").unwrap();
+            let o_reversed = b_builder.reverse(ctx, o_builder).unwrap();
+
+            assert_eq!(b_builder.length(ctx, o_reversed).unwrap(), 7);
+            assert_eq!(b_builder.to_string(ctx, o_reversed).unwrap(), "tluafed");
+
+            let o_string: jstring = b_builder.to_string_as(ctx, o_reversed).unwrap();
+            let b_string = JavaDefaultString::bound(ctx).unwrap();
+            let o_string = JavaDefaultStringRef::downcast(ctx, o_string).unwrap().unwrap();
+
+            assert_eq!(b_string.value_of(ctx, b_string.class(), true).unwrap(), "true");
+            assert!(!b_string.is_empty(ctx, o_string).unwrap());
+            assert_eq!(
+                b_string.to_char_array(ctx, o_string).unwrap(),
+                "tluafed".encode_utf16().collect::<Vec<_>>()
+            );
+        }
+    });
+}
+
+#[test]
+pub fn test_type_mapping() {
+    bind_java! {
+        @ClassName("java.util.UUID")
+        class JavaUuidBits {
+            JavaUuidBits(long most, long least);
+
+            long getMostSignificantBits();
+            long getLeastSignificantBits();
+        }
+    }
+
+    #[derive(Copy, Clone, Debug, Eq, PartialEq)]
+    struct Uuid(i64, i64);
+
+    impl FromJava<jobject> for Uuid {
+        unsafe fn from_java(value: jobject, ctx: Context) -> bind_java::Result<Self> {
+            let bits = JavaUuidBitsRef::downcast(ctx, value)?.unwrap().on(ctx)?;
+
+            Ok(Uuid(bits.get_most_significant_bits()?, bits.get_least_significant_bits()?))
+        }
+    }
+
+    impl IntoJava<jobject> for Uuid {
+        fn into_java(self, ctx: Context) -> bind_java::Result<jobject> {
+            unsafe { JavaUuidBitsRef::statics(ctx)?.new(self.0, self.1).map(Into::into) }
+        }
+    }
+
+    with_java_vm(|ctx| {
+        let classes = compile_file_and_load_classes(
+            ctx,
+            "RustUuidArrays",
+            quote! {
+                import java.util.UUID;
+
+                public class RustUuidArrays {
+                    public static UUID[] reversed(UUID[] values) {
+                        UUID[] result = new UUID[values.length];
+                        for (int i = 0; i < values.length; i += 1) {
+                            result[values.length - 1 - i] = values[i];
+                        }
+                        return result;
+                    }
+                }
+            },
+        );
+
+        bind_java! {
+            map java.util.UUID => Uuid;
+
+            class JavaMappedUuid {
+                static java.util.UUID fromString(java.lang.String value);
+                static java.util.UUID nameUUIDFromBytes(byte[] name);
+
+                int compareTo(java.util.UUID other);
+                java.lang.String toString();
+            }
+
+            @ClassName("RustUuidArrays")
+            class JavaRustUuidArrays {
+                static java.util.UUID[] reversed(java.util.UUID[] values);
+            }
+        }
+
+        bind_java! {
+            map java.util.UUID => Uuid;
+
+            @ClassName("RustUuidArrays")
+            class JavaRustUuidArraysAgain {
+                static java.util.UUID[] reversed(java.util.UUID[] values);
+            }
+        }
+
+        unsafe {
+            let c_uuid = call!(v1_1, ctx, FindClass, c"java/util/UUID".as_ptr());
+            let b_uuid = JavaMappedUuid::bind(ctx, c_uuid).unwrap();
+
+            let uuid = b_uuid
+                .from_string(ctx, c_uuid, "00000000-0000-0001-0000-000000000002")
+                .unwrap();
+            assert_eq!(uuid, Uuid(1, 2));
+
+            let o_uuid = JavaMappedUuidRef::from_object(uuid.into_java(ctx).unwrap());
+            assert_eq!(b_uuid.to_string(ctx, o_uuid).unwrap(), "00000000-0000-0001-0000-000000000002");
+            assert_eq!(b_uuid.compare_to(ctx, o_uuid, Uuid(1, 2)).unwrap(), 0);
+            assert_eq!(b_uuid.compare_to(ctx, o_uuid, Uuid(1, 3)).unwrap(), -1);
+
+            let o_raw: jobject = b_uuid
+                .from_string_as(ctx, c_uuid, "00000000-0000-0001-0000-000000000002")
+                .unwrap();
+            assert!(!o_raw.is_null());
+
+            let b_arrays = JavaRustUuidArrays::bound_in(ctx, &classes).unwrap();
+            let reversed = JavaRustUuidArraysRef::statics_in(ctx, &b_arrays)
+                .reversed(vec![Uuid(1, 2), Uuid(3, 4)])
+                .unwrap();
+            assert_eq!(reversed, vec![Uuid(3, 4), Uuid(1, 2)]);
+
+            let b_again = JavaRustUuidArraysAgain::bound_in(ctx, &classes).unwrap();
+            let reversed = JavaRustUuidArraysAgainRef::statics_in(ctx, &b_again)
+                .reversed(vec![Uuid(5, 6), Uuid(7, 8)])
+                .unwrap();
+            assert_eq!(reversed, vec![Uuid(7, 8), Uuid(5, 6)]);
+        }
+    });
+}
+
+#[test]
+pub fn test_jni_onload() {
+    static LOADED: AtomicBool = AtomicBool::new(false);
+    static UNLOADED: AtomicBool = AtomicBool::new(false);
+
+    bind_java! {
+        @ClassName("java.lang.Integer")
+        class JavaOnLoadInteger {
+            static final int MAX_VALUE;
+        }
+    }
+
+    bind_java::jni_onload!(
+        |ctx| {
+            let b_integer = JavaOnLoadInteger::bound(ctx)?;
+
+            assert_eq!(unsafe { b_integer.get_max_value(ctx, b_integer.class())? }, jint::MAX);
+            LOADED.store(true, Ordering::SeqCst);
+
+            Ok(())
+        },
+        |_| {
+            UNLOADED.store(true, Ordering::SeqCst);
+        }
+    );
+
+    with_java_vm(|ctx| unsafe {
+        let mut vm = null_mut();
+        call!(v1_1, ctx, GetJavaVM, &mut vm);
+
+        assert!(JNI_OnLoad(vm, null_mut()) >= JNI_VERSION_1_8);
+        assert!(LOADED.load(Ordering::SeqCst));
+        assert_eq!(bind_java::java_vm().map(|vm| vm.as_raw()), Some(vm));
+
+        let failed = bind_java::on_load(vm, |ctx| {
+            Err(bind_java::new_throwable(
+                ctx,
+                "java/lang/IllegalStateException",
+                "init failed",
+            ))
+        });
+        assert_eq!(failed, JNI_ERR);
+        assert!(call!(v1_2, ctx, ExceptionCheck));
+        call!(v1_1, ctx, ExceptionClear);
+
+        JNI_OnUnload(vm, null_mut());
+        assert!(UNLOADED.load(Ordering::SeqCst));
+    });
+}
+
+#[test]
+pub fn test_thread_attach() {
+    bind_java! {
+        @ClassName("java.lang.Thread")
+        class JavaAttachThread {
+            static java.lang.Thread currentThread();
+
+            java.lang.String getName();
+            boolean isDaemon();
+        }
+    }
+
+    let vm = crate::vm::java_vm();
+
+    std::thread::spawn(move || unsafe {
+        assert_eq!(vm.get_env(), Err(VmError::Detached));
+
+        {
+            let guard = vm
+                .attach_current_thread_with(AttachOptions {
+                    name: Some("bind-java-worker"),
+                    ..AttachOptions::default()
+                })
+                .unwrap();
+            let ctx = guard.env();
+
+            let thread = JavaAttachThreadRef::statics(ctx).unwrap().current_thread().unwrap();
+            let thread = thread.on(ctx).unwrap();
+            assert_eq!(thread.get_name().unwrap(), "bind-java-worker");
+            assert!(!thread.is_daemon().unwrap());
+
+            {
+                let nested = vm.attach_current_thread().unwrap();
+                assert_eq!(nested.env(), ctx);
+            }
+
+            assert_eq!(vm.get_env(), Ok(ctx));
+            assert_eq!(bind_java::current_env(), Some(ctx));
+        }
+
+        assert_eq!(vm.get_env(), Err(VmError::Detached));
+        assert_eq!(bind_java::current_env(), None);
+    })
+    .join()
+    .unwrap();
+
+    std::thread::spawn(move || unsafe {
+        {
+            let guard = vm.attach_as_daemon().unwrap();
+            let ctx = guard.env();
+
+            let thread = JavaAttachThreadRef::statics(ctx).unwrap().current_thread().unwrap();
+            assert!(thread.on(ctx).unwrap().is_daemon().unwrap());
+            assert_eq!(JavaVm::from_env(ctx), Ok(vm));
+        }
+
+        assert_eq!(vm.get_env(), Err(VmError::Detached));
+
+        let guard = vm.attach_current_thread().unwrap();
+        assert_eq!(bind_java::current_env(), Some(guard.env()));
+
+        vm.detach_current_thread().unwrap();
+        assert_eq!(bind_java::current_env(), None);
+
+        std::mem::forget(guard);
+    })
+    .join()
+    .unwrap();
+}
+
+#[test]
+pub fn test_invocation() {
+    bind_java! {
+        @ClassName("java.lang.System")
+        class JavaInvocationSystem {
+            static java.lang.String getProperty(java.lang.String key);
+        }
+    }
+
+    let vm = crate::vm::java_vm();
+
+    assert_eq!(bind_java::created_java_vms().unwrap(), vec![vm]);
+    assert!(JavaVmBuilder::new().build().is_err());
+
+    with_java_vm(|ctx| unsafe {
+        let value = JavaInvocationSystemRef::statics(ctx)
+            .unwrap()
+            .get_property("bind.java.example")
+            .unwrap();
+
+        assert_eq!(value, "true");
+    })
+}
+
+#[test]
+pub fn test_jni_compat() {
+    bind_java! {
+        @ClassName("java.lang.String")
+        class JavaCompatString {
+            java.lang.String concat(java.lang.String other);
+        }
+    }
+
+    with_java_vm(|ctx| unsafe {
+        let mut env = bind_java::context_into_jni(ctx);
+        assert_eq!(bind_java::context_from_jni(&env), ctx);
+        assert_eq!(JavaVm::from_jni(&env.get_java_vm().unwrap()), crate::vm::java_vm());
+
+        let hello = env.new_string("hello, ").unwrap();
+        let handle: JavaCompatStringRef = (&hello).into_java(ctx).unwrap();
+        let concat = handle.on(ctx).unwrap().concat(env.new_string("jni").unwrap()).unwrap();
+        assert_eq!(concat, "hello, jni");
+
+        let object: Object = "bind-java".into_java(ctx).unwrap();
+        let string = jni::objects::JString::from_java(object, ctx).unwrap();
+        assert_eq!(String::from(env.get_string(&string).unwrap()), "bind-java");
+        assert_eq!(bind_java::object_from_jni(&string), object);
+    })
+}
+
+#[test]
+pub fn test_class_loaders() {
+    with_java_vm(|ctx| unsafe {
+        let system = ObjectClassLoader::system(ctx).unwrap();
+        let class = system.load_class(ctx, "java/util/ArrayList").unwrap();
+        assert!(call!(
+            v1_1,
+            ctx,
+            IsSameObject,
+            class,
+            bind_java::find_class(ctx, "java/util/ArrayList").unwrap()
+        ));
+
+        // threads attached from native code have no context class loader.
+        if let Some(context) = ObjectClassLoader::context(ctx).unwrap() {
+            assert!(call!(v1_1, ctx, IsSameObject, context.object(), system.object()));
+
+            context.delete(ctx);
+        }
+
+        let wrapped = ObjectClassLoader::new(ctx, system.object()).unwrap();
+        assert!(wrapped.load_class(ctx, "rust.Missing").is_err());
+
+        let compiled = compile_file_and_load_classes(
+            ctx,
+            "RustLoaderTest",
+            quote! {
+                public class RustLoaderTest {}
+            },
+        );
+        assert!(system.load_class(ctx, "RustLoaderTest").is_err());
+        let class = compiled.load_class(ctx, "RustLoaderTest").unwrap();
+
+        let url = format!("file://{}/", compiled._class_path.path().display());
+        let child = UrlClassLoader::from_urls(ctx, &[&url], Some(&wrapped)).unwrap();
+        let other = child.load_class(ctx, "RustLoaderTest").unwrap();
+        assert!(!call!(v1_1, ctx, IsSameObject, class, other));
+
+        child.delete(ctx);
+        wrapped.delete(ctx);
+        system.delete(ctx);
+    })
+}
+
+#[test]
+pub fn test_in_memory_classes() {
+    bind_java! {
+        @ClassName("RustMemoryDerived")
+        class JavaRustMemoryDerived {
+            JavaRustMemoryDerived();
+
+            java.lang.String describe();
+        }
+    }
+
+    with_java_vm(|ctx| unsafe {
+        let compiled = compile_file_and_load_classes(
+            ctx,
+            "RustMemoryDerived",
+            quote! {
+                interface RustMemoryNamed {
+                    String name();
+                }
+
+                class RustMemoryBase {
+                    String prefix() {
+                        return "memory:";
+                    }
+                }
+
+                public class RustMemoryDerived extends RustMemoryBase implements RustMemoryNamed {
+                    public String name() {
+                        return "derived";
+                    }
+
+                    public String describe() {
+                        return prefix() + name();
+                    }
+                }
+            },
+        );
+        let class_path = compiled._class_path.path();
+        let read_class = |name: &str| std::fs::read(class_path.join(name).with_extension("class")).unwrap();
+
+        let bytes = read_class("RustMemoryBase");
+        let system = ObjectClassLoader::system(ctx).unwrap();
+        let isolated = UrlClassLoader::from_urls(ctx, &[], Some(&system)).unwrap();
+        let class = bind_java::define_class(ctx, "RustMemoryBase", Some(&isolated), &bytes).unwrap();
+        assert!(call!(
+            v1_1,
+            ctx,
+            IsSameObject,
+            class,
+            isolated.load_class(ctx, "RustMemoryBase").unwrap()
+        ));
+
+        let classes = ["RustMemoryNamed", "RustMemoryBase", "RustMemoryDerived"].map(|n| (n, read_class(n)));
+        let loader = InMemoryClassLoader::new(ctx, classes, None).unwrap();
+        let class = loader.load_class(ctx, "RustMemoryDerived").unwrap();
+        let b_derived = JavaRustMemoryDerived::bind(ctx, class).unwrap();
+        let o_derived = b_derived.new(ctx, class).unwrap();
+        assert_eq!(b_derived.describe(ctx, o_derived).unwrap(), "memory:derived");
+
+        let mut truncated = read_class("RustMemoryDerived");
+        truncated.truncate(16);
+        let truncated_loader = InMemoryClassLoader::new(ctx, [("RustMemoryDerived", truncated)], None).unwrap();
+        let throwable = truncated_loader.load_class(ctx, "RustMemoryDerived").err().unwrap();
+        let c_format_error = bind_java::find_class(ctx, "java/lang/ClassFormatError").unwrap();
+        assert!(bind_java::is_instance_of(ctx, throwable, c_format_error));
+
+        let throwable = truncated_loader.load_class(ctx, "RustMemoryMissing").err().unwrap();
+        let c_not_found = bind_java::find_class(ctx, "java/lang/ClassNotFoundException").unwrap();
+        assert!(bind_java::is_instance_of(ctx, throwable, c_not_found));
+        truncated_loader.delete(ctx);
+
+        let jar_ret = std::process::Command::new("jar")
+            .args([
+                "cf",
+                "classes.jar",
+                "RustMemoryNamed.class",
+                "RustMemoryBase.class",
+                "RustMemoryDerived.class",
+            ])
+            .current_dir(class_path)
+            .status()
+            .unwrap();
+        assert!(jar_ret.success());
+
+        let jar = std::fs::read(class_path.join("classes.jar")).unwrap();
+        assert!(JavaRustMemoryDerived::find_class(ctx, None).is_err());
+        let registered = bind_java::register_jar(ctx, &jar).unwrap();
+
+        let derived = JavaRustMemoryDerivedRef::statics(ctx).unwrap().new().unwrap();
+        assert_eq!(derived.on(ctx).unwrap().describe().unwrap(), "memory:derived");
+
+        assert!(bind_java::unregister_class_loader(&registered));
+        assert!(!bind_java::unregister_class_loader(&registered));
+        assert!(JavaRustMemoryDerived::find_class(ctx, None).is_err());
+        Arc::into_inner(registered).unwrap().delete(ctx);
+
+        loader.delete(ctx);
+        isolated.delete(ctx);
+        system.delete(ctx);
+    })
+}
+
+#[test]
+pub fn test_app_class_loader() {
+    with_java_vm(|ctx| unsafe {
+        assert_eq!(
+            bind_java::class_loader_fallback(),
+            bind_java::ClassLoaderFallback::AppClassLoader
+        );
+
+        let compiled = compile_file_and_load_classes(
+            ctx,
+            "RustAppLoaderEntry",
+            quote! {
+                public class RustAppLoaderEntry {
+                    static native boolean enter();
+                }
+            },
+        );
+        let bytes = std::fs::read(compiled._class_path.path().join("RustAppLoaderEntry.class")).unwrap();
+
+        // natives of a bootstrap class resolve FindClass against the bootstrap loader, which can't see java.sql.
+        let c_entry = bind_java::define_class(ctx, "RustAppLoaderEntry", None, &bytes).unwrap();
+        bind_java::register_native_method(
+            ctx,
+            c_entry,
+            "enter",
+            "()Z",
+            system_fn!(|ctx: Context, _: Class| -> Result<bool> {
+                let loader = bind_java::app_class_loader().expect("captured when entered from java");
+
+                unsafe {
+                    let name = c"java/sql/Connection";
+                    let class = call!(v1_1, ctx, FindClass, name.as_ptr());
+                    assert!(class.is_null());
+                    call!(v1_1, ctx, ExceptionClear);
+
+                    let class = bind_java::find_class(ctx, "java/sql/Connection")?;
+                    let expected = loader.load_class(ctx, "java/sql/Connection")?;
+
+                    Ok(call!(v1_1, ctx, IsSameObject, class, expected))
+                }
+            }) as *const (),
+        )
+        .unwrap();
+
+        let enter = bind_java::find_static_method(ctx, c_entry, "enter", "()Z").unwrap();
+        assert!(bind_java::call_static_method::<bool, bool>(ctx, c_entry, enter, &[]).unwrap());
+
+        let loader = bind_java::app_class_loader().unwrap();
+        assert!(std::ptr::eq(loader, bind_java::capture_app_class_loader(ctx).unwrap()));
+
+        let throwable = bind_java::find_class(ctx, "rust/MissingApplicationClass").unwrap_err();
+        let class = bind_java::find_class(ctx, "java/lang/NoClassDefFoundError").unwrap();
+        assert!(bind_java::is_instance_of(ctx, throwable, class));
+    })
+}
+
+#[test]
+pub fn test_collections() {
+    bind_java! {
+        @ClassName("java.util.Collections")
+        class JavaCollections {
+            static java.util.List unmodifiableList(java.util.List list);
+            static java.util.Map unmodifiableMap(java.util.Map map);
+            static java.util.Set unmodifiableSet(java.util.Set set);
+            static java.util.List emptyList();
+        }
+    }
+
+    with_java_vm(|ctx| unsafe {
+        let collections = JavaCollectionsRef::statics(ctx).unwrap();
+
+        let list = vec!["a".to_owned(), "b".to_owned(), "c".to_owned()];
+        let r_list: Vec<String> = collections.unmodifiable_list_as(list.clone()).unwrap();
+        assert_eq!(r_list, list);
+
+        let empty: bind_java::ListRef = collections.empty_list().unwrap();
+        assert_eq!(Vec::<String>::from_java(empty, ctx).unwrap(), Vec::<String>::new());
+
+        let map = HashMap::from([("one".to_owned(), "1".to_owned()), ("two".to_owned(), "2".to_owned())]);
+        let r_map: HashMap<String, String> = collections.unmodifiable_map_as(map.clone()).unwrap();
+        assert_eq!(r_map, map);
+
+        let ordered = BTreeMap::from([("x".to_owned(), "24".to_owned()), ("y".to_owned(), "25".to_owned())]);
+        let r_ordered: BTreeMap<String, String> = collections.unmodifiable_map_as(ordered.clone()).unwrap();
+        assert_eq!(r_ordered, ordered);
+
+        let set = HashSet::from(["red".to_owned(), "green".to_owned()]);
+        let r_set: HashSet<String> = collections.unmodifiable_set_as(set.clone()).unwrap();
+        assert_eq!(r_set, set);
+
+        let large = (0..50_000).map(|i| (i.to_string(), i)).collect::<HashMap<_, _>>();
+        let r_large: HashMap<String, i32> = collections.unmodifiable_map_as(large.clone()).unwrap();
+        assert_eq!(r_large, large);
+    })
+}
+
+#[test]
+pub fn test_boxed_primitives() {
+    bind_java! {
+        @ClassName("java.util.Objects")
+        class JavaBoxedObjects {
+            static java.lang.Object requireNonNullElse(java.lang.Object value, java.lang.Object fallback);
+        }
+
+        class JavaBoxedInteger {
+            static java.lang.Integer getInteger(java.lang.String name);
+            static java.lang.Integer valueOf(int value);
+        }
+    }
+
+    with_java_vm(|ctx| unsafe {
+        let objects = JavaBoxedObjectsRef::statics(ctx).unwrap();
+
+        assert!(objects.require_non_null_else_as::<bool>(true, false).unwrap());
+        assert_eq!(objects.require_non_null_else_as::<i8>(-8i8, 0i8).unwrap(), -8);
+        assert_eq!(objects.require_non_null_else_as::<i16>(1600i16, 0i16).unwrap(), 1600);
+        assert_eq!(objects.require_non_null_else_as::<i64>(i64::MAX, 0i64).unwrap(), i64::MAX);
+        assert_eq!(objects.require_non_null_else_as::<f32>(1.5f32, 0f32).unwrap(), 1.5);
+        assert_eq!(objects.require_non_null_else_as::<f64>(2.25f64, 0f64).unwrap(), 2.25);
+        assert_eq!(objects.require_non_null_else_as::<char>('字', 'a').unwrap(), '字');
+        assert_eq!(objects.require_non_null_else_as::<i32>(None::<i32>, 7).unwrap(), 7);
+        assert!(IntoJava::<Object>::into_java('😀', ctx).is_err());
+
+        let c_integer = bind_java::find_class(ctx, "java/lang/Integer").unwrap();
+        let b_integer = JavaBoxedInteger::bind(ctx, c_integer).unwrap();
+        assert_eq!(b_integer.value_of_as::<i32>(ctx, c_integer, 42).unwrap(), 42);
+        assert_eq!(
+            b_integer
+                .get_integer_as::<Option<i32>>(ctx, c_integer, "bind.java.missing")
+                .unwrap(),
+            None
+        );
+        assert!(b_integer.get_integer_as::<i32>(ctx, c_integer, "bind.java.missing").is_err());
+
+        let list: Vec<i64> = vec![1, 2, 3];
+        let o_list: bind_java::ListRef = list.clone().into_java(ctx).unwrap();
+        let r_list: Vec<i64> = Vec::from_java(o_list, ctx).unwrap();
+        assert_eq!(r_list, list);
+    })
+}
+
+#[test]
+pub fn test_object_arrays() {
+    with_java_vm(|ctx| {
+        let loader = Arc::new(compile_file_and_load_classes(
+            ctx,
+            "RustArrayTest",
+            quote! {
+                class RustArrayItem {
+                    final String name;
+
+                    RustArrayItem(String name) {
+                        this.name = name;
+                    }
+                }
+
+                public class RustArrayTest {
+                    public static String names(RustArrayItem[] items) {
+                        StringBuilder result = new StringBuilder(items.getClass().getComponentType().getName());
+                        for (RustArrayItem item : items) {
+                            result.append(":").append(item.name);
+                        }
+                        return result.toString();
+                    }
+
+                    public static int[][] transpose(int[][] matrix) {
+                        int[][] result = new int[matrix[0].length][matrix.length];
+                        for (int i = 0; i < matrix.length; i += 1) {
+                            for (int j = 0; j < matrix[i].length; j += 1) {
+                                result[j][i] = matrix[i][j];
+                            }
+                        }
+                        return result;
+                    }
+
+                    public static Integer[] reverse(Integer[] values) {
+                        Integer[] result = new Integer[values.length];
+                        for (int i = 0; i < values.length; i += 1) {
+                            result[values.length - i - 1] = values[i];
+                        }
+                        return result;
+                    }
+
+                    public static String join(StringBuilder[] builders) {
+                        return builders.getClass().getComponentType().getName() + ":" + String.join(",", builders);
+                    }
+
+                    public static String[][] split(String[] values) {
+                        String[][] result = new String[values.length][];
+                        for (int i = 0; i < values.length; i += 1) {
+                            result[i] = values[i].split(",");
+                        }
+                        return result;
+                    }
+                }
+            },
+        ));
+
+        bind_java! {
+            @ClassName("RustArrayTest")
+            class JavaRustArrayTest {
+                static int[][] transpose(int[][] matrix);
+                static java.lang.Integer[] reverse(java.lang.Integer[] values);
+                static java.lang.String join(java.lang.StringBuilder[] builders);
+                static java.lang.String[][] split(java.lang.String[] values);
+                static java.lang.String names(RustArrayItem[] items);
+            }
+
+            @ClassName("RustArrayItem")
+            class JavaRustArrayItem {
+                JavaRustArrayItem(java.lang.String name);
+            }
+
+            @ClassName("java.lang.StringBuilder")
+            class JavaArrayStringBuilder {
+                JavaArrayStringBuilder(java.lang.String value);
+            }
+        }
+
+        unsafe {
+            let b_test = JavaRustArrayTest::bound_in(ctx, &*loader).unwrap();
+            let b_builder = JavaArrayStringBuilder::bound(ctx).unwrap();
+
+            let matrix: Vec<Vec<i32>> = vec![vec![1, 2, 3], vec![4, 5, 6]];
+            let r_matrix: Vec<Vec<i32>> = b_test.transpose_as(ctx, b_test.class(), matrix).unwrap();
+            assert_eq!(r_matrix, vec![vec![1, 4], vec![2, 5], vec![3, 6]]);
+
+            let values = [Some(1), None, Some(3)];
+            let r_values: Vec<Option<i32>> = b_test.reverse_as(ctx, b_test.class(), &values[..]).unwrap();
+            assert_eq!(r_values, vec![Some(3), None, Some(1)]);
+
+            let builders = (0..256)
+                .map(|i| b_builder.new(ctx, b_builder.class(), i.to_string().as_str()).unwrap())
+                .collect::<Vec<JavaArrayStringBuilderRef>>();
+            let joined: String = b_test.join_as(ctx, b_test.class(), builders).unwrap();
+            assert!(joined.starts_with("java.lang.StringBuilder:0,1,2,"));
+            assert!(joined.ends_with(",254,255"));
+
+            let split: Vec<Vec<String>> = b_test.split_as(ctx, b_test.class(), vec!["a,b", "c"]).unwrap();
+            assert_eq!(split, vec![vec!["a".to_owned(), "b".to_owned()], vec!["c".to_owned()]]);
+
+            let b_item = JavaRustArrayItem::bound_in(ctx, &*loader).unwrap();
+            let items = ["x", "y"].map(|name| b_item.new(ctx, b_item.class(), name).unwrap()).to_vec();
+            assert_eq!(b_test.names(ctx, b_test.class(), items).unwrap(), "RustArrayItem:x:y");
+        }
+    });
+}
+
+#[test]
+pub fn test_array_views() {
+    with_java_vm(|ctx| unsafe {
+        let o_raw: jintArray = vec![1, 2, 3, 4].into_java(ctx).unwrap();
+        assert!(PrimitiveArrayRef::<i64>::downcast(ctx, o_raw).unwrap().is_none());
+        let mut o_array = PrimitiveArrayRef::<jint>::downcast(ctx, o_raw).unwrap().unwrap();
+
+        {
+            let mut elements = ArrayElements::new(ctx, &mut o_array, ReleaseMode::CopyBack).unwrap();
+            elements.iter_mut().for_each(|v| *v *= 10);
+        }
+
+        let mut region = [0; 4];
+        get_array_region(ctx, o_array, 0, &mut region).unwrap();
+        assert_eq!(region, [10, 20, 30, 40]);
+
+        let mut elements = ArrayElements::new(ctx, &mut o_array, ReleaseMode::CopyBack).unwrap();
+        let is_copy = elements.is_copy();
+        elements[0] = -1;
+        elements.abort();
+
+        get_array_region(ctx, o_array, 0, &mut region[..1]).unwrap();
+        assert_eq!(region[0], if is_copy { 10 } else { -1 });
+
+        {
+            let mut critical = CriticalArray::new(ctx, &mut o_array, ReleaseMode::CopyBack).unwrap();
+            assert_eq!(critical.len(), 4);
+            critical[3] = 400;
+        }
+
+        set_array_region(ctx, o_array, 1, &[200, 300]).unwrap();
+        get_array_region(ctx, o_array, 1, &mut region[1..]).unwrap();
+        assert_eq!(region[1..], [200, 300, 400]);
+
+        assert!(set_array_region(ctx, o_array, 3, &[1, 2]).is_err());
+        let mut o_null = PrimitiveArrayRef::<jint>::from_object(null_mut());
+        assert!(get_array_region(ctx, o_null, 0, &mut region).is_err());
+        assert!(ArrayElements::new(ctx, &mut o_null, ReleaseMode::Abort).is_err());
+
+        let values = Vec::<jint>::from_java(o_raw, ctx).unwrap();
+        assert_eq!(values[1..], [200, 300, 400]);
+    })
+}
+
+#[test]
+pub fn test_direct_buffers() {
+    with_java_vm(|ctx| {
+        let loader = compile_file_and_load_classes(
+            ctx,
+            "RustBufferTest",
+            quote! {
+                import java.nio.ByteBuffer;
+
+                public class RustBufferTest {
+                    public static int sum(ByteBuffer buffer) {
+                        int sum = 0;
+                        while (buffer.hasRemaining()) {
+                            sum += buffer.get();
+                        }
+                        return sum;
+                    }
+
+                    public static boolean fill(ByteBuffer buffer, byte value) {
+                        if (buffer.isReadOnly()) {
+                            return false;
+                        }
+                        while (buffer.hasRemaining()) {
+                            buffer.put(value);
+                        }
+                        return true;
+                    }
+
+                    public static ByteBuffer allocate(int capacity) {
+                        ByteBuffer buffer = ByteBuffer.allocateDirect(capacity);
+                        for (int i = 0; i < capacity; i += 1) {
+                            buffer.put(i, (byte) i);
+                        }
+                        return buffer;
+                    }
+
+                    public static ByteBuffer window(int capacity) {
+                        ByteBuffer buffer = allocate(capacity);
+                        buffer.position(1);
+                        buffer.limit(capacity - 1);
+                        return buffer;
+                    }
+                }
+            },
+        );
+
+        bind_java! {
+            @ClassName("RustBufferTest")
+            class JavaRustBufferTest {
+                static int sum(java.nio.ByteBuffer buffer);
+                static boolean fill(java.nio.ByteBuffer buffer, byte value);
+                static java.nio.ByteBuffer allocate(int capacity);
+                static java.nio.ByteBuffer window(int capacity);
+            }
+
+            @ClassName("java.lang.System")
+            class JavaBufferSystem {
+                static void gc();
+            }
+        }
+
+        unsafe {
+            let b_test = JavaRustBufferTest::bound_in(ctx, &loader).unwrap();
+            let class = b_test.class();
+
+            let sum: jint = b_test.sum(ctx, class, vec![1u8, 2, 3]).unwrap();
+            assert_eq!(sum, 6);
+            let sum: jint = b_test.sum(ctx, class, vec![4u8, 5].into_boxed_slice()).unwrap();
+            assert_eq!(sum, 9);
+
+            let data = [7u8; 4];
+            let read_only = BorrowedBuffer::read_only(ctx, &data).unwrap();
+            let filled: bool = b_test.fill(ctx, class, &read_only, 1i8).unwrap();
+            read_only.delete(ctx);
+            assert!(!filled);
+
+            let mut data = [0u8; 4];
+            let borrowed = BorrowedBuffer::new(ctx, &mut data).unwrap();
+            let filled: bool = b_test.fill(ctx, class, &borrowed, 9i8).unwrap();
+            borrowed.delete(ctx);
+            assert!(filled);
+            assert_eq!(data, [9; 4]);
+
+            let mut o_buffer: ByteBufferRef = b_test.allocate(ctx, class, 5).unwrap();
+            assert_eq!(o_buffer.capacity(ctx), Some(5));
+            o_buffer.as_mut_slice(ctx).unwrap()[0] = 42;
+            assert_eq!(o_buffer.as_slice(ctx).unwrap()[..2], [42, 1]);
+            let values: Vec<u8> = Vec::from_java(o_buffer, ctx).unwrap();
+            assert_eq!(values, vec![42, 1, 2, 3, 4]);
+            let values: Vec<u8> = b_test.window_as(ctx, class, 5).unwrap();
+            assert_eq!(values, vec![1, 2, 3]);
+
+            let o_owned: ByteBufferRef = vec![0u8; 1024].into_java(ctx).unwrap();
+            call!(v1_1, ctx, DeleteLocalRef, o_owned.as_object());
+
+            let statics = JavaBufferSystemRef::statics(ctx).unwrap();
+            let released = (0..10).any(|_| {
+                statics.gc().unwrap();
+                bind_java::release_collected_buffers(ctx) > 0
+            });
+            assert!(released);
+        }
+    });
+}
+
+#[test]
+pub fn test_native_types() {
+    bind_java! {
+        @ClassName("java.lang.Character")
+        class JavaNativeCharacter {
+            static boolean isLetter(char ch);
+            static char toUpperCase(char ch);
+            static char highSurrogate(int codePoint);
+        }
+
+        @ClassName("java.lang.Byte")
+        class JavaNativeByte {
+            static int toUnsignedInt(byte value);
+            static byte parseByte(java.lang.String value);
+        }
+
+        @ClassName("java.lang.Short")
+        class JavaNativeShort {
+            static int toUnsignedInt(short value);
+            static short parseShort(java.lang.String value);
+        }
+    }
+
+    with_java_vm(|ctx| unsafe {
+        let character = JavaNativeCharacterRef::statics(ctx).unwrap();
+
+        assert!(character.is_letter('a').unwrap());
+        assert!(!character.is_letter('1').unwrap());
+        assert_eq!(character.to_upper_case('ß').unwrap(), 'ß');
+        assert_eq!(character.to_upper_case('é').unwrap(), 'É');
+        assert!(character.high_surrogate(0x1F600).is_err());
+        assert_eq!(character.high_surrogate_as::<u16>(0x1F600).unwrap(), 0xD83D);
+
+        let byte = JavaNativeByteRef::statics(ctx).unwrap();
+        assert_eq!(byte.to_unsigned_int(200u8).unwrap(), 200);
+        assert_eq!(byte.parse_byte_as::<u8>("-1").unwrap(), u8::MAX);
+        assert_eq!(byte.parse_byte("-1").unwrap(), -1i8);
+
+        let short = JavaNativeShortRef::statics(ctx).unwrap();
+        assert_eq!(short.to_unsigned_int(u16::MAX).unwrap(), 65535);
+        assert_eq!(short.parse_short_as::<u16>("-2").unwrap(), u16::MAX - 1);
+    })
+}
@@ -1,14 +1,15 @@
 use std::sync::OnceLock;
 
-use jni::JavaVM;
+use bind_java::{Context, JavaVm, JavaVmBuilder};
 
-use bind_java::Context;
+pub fn java_vm() -> JavaVm {
+    static VM: OnceLock<JavaVm> = OnceLock::new();
+
+    *VM.get_or_init(|| JavaVmBuilder::new().property("bind.java.example", "true").build().unwrap())
+}
 
 pub fn with_java_vm<R, F: FnOnce(Context) -> R>(f: F) -> R {
-    static VM: OnceLock<JavaVM> = OnceLock::new();
-    let vm = VM.get_or_init(|| JavaVM::new(jni::InitArgsBuilder::new().build().unwrap()).unwrap());
-    let env = vm.attach_current_thread().unwrap();
+    let guard = java_vm().attach_current_thread().unwrap();
 
-    // temp workaround for jni crate not match jni-sys
-    f(unsafe { std::mem::transmute(env.get_raw()) })
+    f(guard.env())
 }